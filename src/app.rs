@@ -1,14 +1,96 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use ears::AudioController;
 use midgar::{App, Midgar, KeyCode};
 
-use config::SCREEN_SIZE;
-use renderer::GameRenderer;
-use world::GameWorld;
+use assets::Assets;
+use autopilot;
+use bindings::{Action, Binding, Bindings};
+use cli::Options;
+use config::{GameConfig, MAX_FRAME_TIME};
+use controls;
+use daily::DailyResults;
+use date::Date;
+use evolution::{self, Evolution};
+use genome::Genome;
+use ghost::Ghost;
+use highscores::{HighScore, HighScoreTable, MAX_HIGH_SCORES};
+use input::{GameInput, MAX_PLAYERS, MenuInput};
+use menu::{MenuAction, MenuScreen, Menus};
+use mode::GameMode;
+use paths;
+use renderer::{FrameStats, GameRenderer, Scoreboard};
+use replay::{Replay, ReplayPlayer};
+use rng;
+use settings::{MAX_SCALE, Settings};
+use sounds::Sounds;
+use watcher::FileWatcher;
+use world::{GameEvent, GameState, GameWorld};
+
+
+// How long the title screen sits untouched before the autopilot starts a
+// demo run, in seconds.
+const ATTRACT_DELAY: f64 = 10.0;
+// How long a demo run's game over screen stays up, in seconds.
+const DEMO_GAME_OVER_TIME: f32 = 3.0;
+// How many times faster than real time F runs an evolution.
+const FAST_FORWARD: u32 = 8;
 
 
 pub struct GameApp<'a> {
     world: GameWorld,
     renderer: GameRenderer<'a>,
+    sounds: Sounds,
+    high_scores: HighScoreTable,
+    player_name: Option<String>,
+    // Seed asked for on the command line, used outside the daily challenge.
+    fixed_seed: Option<u64>,
+
+    daily: DailyResults,
+    // Day of the daily challenge being played.
+    daily_date: Date,
+    // Whether the current daily run is the day's scored attempt.
+    daily_scored: bool,
+    // Seed and mode the world's ghost was looked up for.
+    ghost_key: Option<(u64, GameMode)>,
+
+    // Whether the autopilot is flying instead of the players.
+    autopilot: bool,
+    // Whether the autopilot has flown any of the current run, which keeps it
+    // off the scoreboards.
+    autopilot_used: bool,
+    // Set while the autopilot flies a demo run behind the title screen.
+    demo: bool,
+    // Time the title screen has gone without input.
+    idle_time: f64,
+
+    // Set when watching birds learn to fly instead of playing.
+    evolution: Option<Evolution>,
+    fast_forward: bool,
+
+    settings: Settings,
+    menus: Menus,
+    // Set while waiting for a control to bind to an action.
+    rebinding: Option<Action>,
+
+    // Unsimulated time carried over between frames.
+    accumulator: f64,
+    // Input gathered since the last simulation tick.
+    pending_input: GameInput,
+    // Set when watching a replay instead of playing.
+    replay_player: Option<ReplayPlayer>,
+    had_focus: bool,
 
+    // Picks up edits to the config and assets while the game runs.
+    watcher: FileWatcher,
+    config_path: PathBuf,
+
+    // Whether the debug overlay is up, and the frame rate it shows, sampled
+    // once a second.
+    debug: bool,
+    frame_stats: FrameStats,
     time_to_fps: f64,
 }
 
@@ -17,57 +99,709 @@ impl<'a> App for GameApp<'a> {
         // TODO: Query screen size and store info for renderer/world to use.
         // TODO: Keep separate world/screen (pixel) coordinates.
 
-        let (screen_width, screen_height) = midgar.graphics().screen_size();
-        let game_width = 136.0f32;
-        let game_height = screen_height as f32 / (screen_width as f32 / game_width);
-        println!("Screen: {:?}, Game: {:?}", (screen_width, screen_height), (game_width, game_height));
+        // NOTE: main() has already validated the options. The config was
+        // fine then too, but it can be edited before we get here, and there's
+        // no handing main()'s copy over to create().
+        let options = Options::from_env().unwrap();
+        let game_config = match options.load_config() {
+            Ok(game_config) => game_config,
+            Err(e) => {
+                println!("{}, using the default config", e);
+                GameConfig::default()
+            },
+        };
+
+        let screen_size = midgar.graphics().screen_size();
+        let (game_width, game_height) = game_config.screen.game_size();
+        println!("Screen: {:?}, Game: {:?}", screen_size, (game_width, game_height));
+        let config_path = options.config.clone().unwrap_or_else(GameConfig::default_path);
+        let mut watched = Assets::paths();
+        watched.push(config_path.clone());
+        // Likewise the replay, which falls back to a normal run.
+        let replay = options.replay.as_ref().and_then(|path| match Replay::load(path) {
+            Ok(replay) => Some(replay),
+            Err(e) => {
+                println!("{}, starting a normal run", e);
+                None
+            },
+        });
+        let mode = match replay {
+            Some(ref replay) => replay.mode,
+            None => options.mode.unwrap_or(GameMode::Classic),
+        };
+
+        let high_scores = HighScoreTable::load(HighScoreTable::default_path(mode));
+        let settings = Settings::load(Settings::default_path());
+        controls::check(&settings.bindings);
+        let mut menus = Menus::new(&settings);
+        menus.refresh_mode(mode);
+
+        let mut world = match replay {
+            Some(ref replay) => {
+                let (game_width, game_height) = replay.game_size;
+                println!("Playing replay with seed {}", replay.seed);
+                let mut world = GameWorld::new(game_width, game_height, replay.seed, game_config);
+                world.set_tick_rate(replay.tick_rate);
+                world.set_reuse_seed(true);
+                world
+            },
+            None => {
+                let seed = options.seed.unwrap_or_else(rng::random_seed);
+                println!("Seed: {}", seed);
+                let mut world = GameWorld::new(game_width, game_height, seed, game_config);
+                world.set_reuse_seed(options.seed.is_some());
+                world.show_menu();
+                world
+            },
+        };
+        let players = match replay {
+            Some(ref replay) => replay.flaps.len(),
+            None => options.players.unwrap_or(1),
+        };
+        world.set_players(players);
+        if replay.is_some() {
+            world.set_mode(mode);
+        }
+        world.set_high_score(high_scores.best());
+
+        let evolution = if options.evolve {
+            let seed = options.seed.unwrap_or_else(rng::random_seed);
+            let ancestor = options.genome.as_ref().map(|path| evolution::load_ancestor(path).unwrap());
+            let population = options.population.unwrap_or(evolution::DEFAULT_POPULATION);
+            println!("Evolving {} birds from seed {}", population, seed);
+            Some(Evolution::new(game_width, game_height, seed, world.config(), population, ancestor))
+        } else {
+            None
+        };
 
-        GameApp {
-            world: GameWorld::new(game_width, game_height),
+        let mut app = GameApp {
+            world,
             renderer: GameRenderer::new(midgar),
+            sounds: Sounds::new(),
+            high_scores,
+            player_name: options.name,
+            fixed_seed: options.seed,
+
+            daily: DailyResults::load(DailyResults::default_path()),
+            daily_date: Date::today(),
+            daily_scored: false,
+            ghost_key: None,
+
+            autopilot: options.autopilot,
+            autopilot_used: false,
+            demo: false,
+            idle_time: 0.0,
 
-            time_to_fps: 1.0,
+            evolution,
+            fast_forward: false,
+
+            settings,
+            menus,
+            rebinding: None,
+
+            accumulator: 0.0,
+            pending_input: GameInput::new(),
+            replay_player: replay.map(ReplayPlayer::new),
+            had_focus: true,
+
+            watcher: FileWatcher::new(watched),
+            config_path,
+
+            debug: false,
+            frame_stats: FrameStats::default(),
+            time_to_fps: 0.0,
+        };
+        app.menus.refresh_players(players);
+        if app.replay_player.is_none() {
+            app.set_mode(mode);
         }
+        app
     }
 
     fn step(&mut self, midgar: &mut Midgar) {
-        if midgar.input().was_key_pressed(KeyCode::Escape) {
-            midgar.set_should_exit();
-            return;
-        }
-
         let dt = midgar.time().delta_time();
 
+        self.reload_changed_files(midgar, dt);
+
         // TODO: Process input?
         if midgar.input().was_key_pressed(KeyCode::Num1) {
-            let scale = 1;
-            midgar.graphics_mut().set_size(SCREEN_SIZE.0 * scale, SCREEN_SIZE.1 * scale);
+            self.set_scale(midgar, 1);
         } else if midgar.input().was_key_pressed(KeyCode::Num2) {
-            let scale = 2;
-            midgar.graphics_mut().set_size(SCREEN_SIZE.0 * scale, SCREEN_SIZE.1 * scale);
+            self.set_scale(midgar, 2);
         } else if midgar.input().was_key_pressed(KeyCode::Num3) {
-            let scale = 3;
-            midgar.graphics_mut().set_size(SCREEN_SIZE.0 * scale, SCREEN_SIZE.1 * scale);
+            self.set_scale(midgar, 3);
         } else if midgar.input().was_key_pressed(KeyCode::Num4) {
-            let scale = 4;
-            midgar.graphics_mut().set_size(SCREEN_SIZE.0 * scale, SCREEN_SIZE.1 * scale);
+            self.set_scale(midgar, 4);
+        }
+
+        if self.evolution.is_some() {
+            self.step_evolution(midgar, dt);
+            return;
+        }
+
+        if midgar.input().was_key_pressed(KeyCode::F3) {
+            self.debug = !self.debug;
+        }
+
+        if midgar.input().was_key_pressed(KeyCode::F2) && self.replay_player.is_none() && !self.demo {
+            self.autopilot = !self.autopilot;
+            if self.autopilot {
+                self.autopilot_used = true;
+                self.daily_scored = false;
+            }
         }
 
-        // Update game world.
-        self.world.update(midgar, dt as f32);
+        // Don't let a run carry on while the player is looking elsewhere.
+        let has_focus = window_has_focus(midgar);
+        if self.had_focus && !has_focus {
+            self.world.pause();
+        }
+        self.had_focus = has_focus;
+
+        let bindings = &self.settings.bindings;
+        let pause_pressed = controls::was_pressed(midgar, bindings, Action::Pause);
+        let menu_input = read_menu_input(midgar, bindings, &self.renderer);
+
+        if self.demo {
+            // Any key, or the demo run ending, goes back to the title screen.
+            let input = read_input(midgar, bindings);
+            if pause_pressed || menu_input != MenuInput::default() ||
+                input.flaps.iter().any(|&flap| flap) ||
+                (self.world.is_game_over() && self.world.game_over_time() >= DEMO_GAME_OVER_TIME) {
+                self.stop_demo();
+            }
+        } else if let Some(action) = self.rebinding {
+            // The next control pressed is the action's new binding, unless
+            // it's Escape or one that goes back, which leaves it as it was.
+            if let Some(binding) = controls::pressed_binding(midgar) {
+                if binding == Binding::key("Escape") || bindings.get(Action::Back).contains(&binding) {
+                    self.rebinding = None;
+                    self.menus.refresh_bindings(&self.settings.bindings);
+                } else {
+                    // A control that's taken leaves it waiting for another.
+                    match self.settings.bindings.bind(action, binding.clone()) {
+                        Ok(()) => {
+                            println!("Bound {} to {}", binding, action);
+                            self.rebinding = None;
+                            self.save_settings();
+                        },
+                        Err(e) => println!("Could not bind {}: {}", action, e),
+                    }
+                }
+            }
+        } else if self.world.game_state() == GameState::Menu {
+            // The world only sees input once a run has started.
+            if menu_input == MenuInput::default() {
+                self.idle_time += dt;
+            } else {
+                self.idle_time = 0.0;
+            }
+            if let Some(action) = self.menus.update(&menu_input) {
+                self.on_menu_action(midgar, action);
+            } else if self.idle_time >= ATTRACT_DELAY && self.menus.screen() == MenuScreen::Title &&
+                self.replay_player.is_none() {
+                self.start_demo();
+            }
+        } else if self.world.game_state() == GameState::Paused {
+            if self.world.resume_countdown().is_some() {
+                // Pausing again stops the countdown.
+                self.pending_input.pause |= pause_pressed;
+            } else if let Some(action) = self.menus.update_pause(&menu_input) {
+                self.on_menu_action(midgar, action);
+            }
+        } else if self.world.is_game_over() {
+            // Ignore input until the grace period is up, so a late flap
+            // doesn't pick an option by accident.
+            if self.world.can_restart() {
+                if let Some(action) = self.menus.update_game_over(&menu_input) {
+                    self.on_menu_action(midgar, action);
+                }
+            }
+        } else {
+            // Latch input until a simulation tick consumes it, so presses made
+            // on frames that don't run a tick aren't lost.
+            self.pending_input.merge(&read_input(midgar, bindings));
+            self.pending_input.pause |= pause_pressed;
+        }
 
-        // Render game world.
-        self.renderer.render(midgar, dt as f32, &self.world);
+        // Update game world in fixed steps.
+        let tick_dt = 1.0 / self.world.tick_rate() as f64;
+        self.accumulator += dt.min(MAX_FRAME_TIME);
+        while self.accumulator >= tick_dt {
+            let input = match self.replay_player {
+                Some(ref mut player) => {
+                    // Flaps come from the replay, but let the viewer restart
+                    // or pause it.
+                    let mut input = player.input(self.world.run_tick());
+                    input.restart = self.pending_input.restart;
+                    input.pause = self.pending_input.pause;
+                    input
+                },
+                None => {
+                    let mut input = self.pending_input;
+                    if self.autopilot || self.demo {
+                        input.flaps = autopilot::input(&self.world).flaps;
+                    }
+                    input
+                },
+            };
+            self.world.update(&input, tick_dt as f32);
+            self.pending_input = GameInput::new();
+            self.accumulator -= tick_dt;
 
-        // Print FPS every second.
+            self.handle_events();
+        }
+        self.refresh_ghost();
+
+        // Render game world, interpolating between the last two ticks.
+        let alpha = (self.accumulator / tick_dt) as f32;
+        let scores = match self.world.mode() {
+            GameMode::Daily => Scoreboard::Daily {
+                results: &self.daily,
+                date: self.daily_date,
+                scored: self.daily_scored,
+            },
+            _ => Scoreboard::HighScores(&self.high_scores),
+        };
+        // Sample the frame rate every second, for the debug overlay.
         self.time_to_fps -= dt;
         if self.time_to_fps <= 0.0 {
-            println!("FPS: {:.2}, Frame time: {:.2} ms", midgar.fps(), midgar.frame_time() * 1000.0);
+            self.frame_stats = FrameStats {
+                fps: midgar.fps(),
+                frame_time: midgar.frame_time(),
+            };
             self.time_to_fps = 1.0;
         }
+        let debug = if self.debug { Some(&self.frame_stats) } else { None };
+        self.renderer.render(midgar, alpha, &self.world, &scores, &self.menus, self.autopilot, debug);
     }
 
     fn resize(&mut self, size: (u32, u32), midgar: &Midgar) {
         //self.renderer.resize(size);
     }
 }
+
+impl<'a> GameApp<'a> {
+    fn on_menu_action(&mut self, midgar: &mut Midgar, action: MenuAction) {
+        match action {
+            MenuAction::Play => {
+                self.prepare_run();
+                self.world.start();
+            },
+            MenuAction::ToggleMode => {
+                let mode = self.world.mode().next();
+                self.set_mode(mode);
+            },
+            MenuAction::TogglePlayers => {
+                let players = self.world.players().len() % MAX_PLAYERS + 1;
+                self.world.set_players(players);
+                self.menus.refresh_players(players);
+            },
+            MenuAction::HighScores => self.menus.set_screen(MenuScreen::HighScores),
+            MenuAction::Settings => self.menus.set_screen(MenuScreen::Settings),
+            MenuAction::Controls => self.menus.set_screen(MenuScreen::Controls),
+            MenuAction::Quit => midgar.set_should_exit(),
+            MenuAction::ToggleScale => {
+                let scale = self.settings.scale % MAX_SCALE + 1;
+                self.set_scale(midgar, scale);
+            },
+            MenuAction::ToggleSound => {
+                self.settings.sound = !self.settings.sound;
+                self.save_settings();
+            },
+            MenuAction::Rebind(action) => {
+                self.rebinding = Some(action);
+                self.menus.show_rebinding(action);
+            },
+            MenuAction::ResetControls => {
+                self.settings.bindings = Bindings::default();
+                self.save_settings();
+            },
+            // Restarting goes through the world's input like any other.
+            MenuAction::Retry => {
+                self.prepare_run();
+                self.pending_input.restart = true;
+            },
+            MenuAction::Resume => {
+                self.pending_input.pause = true;
+                self.menus.reset_pause();
+            },
+            MenuAction::MainMenu => if self.replay_player.is_some() {
+                midgar.set_should_exit();
+            } else {
+                // Walking away from the day's attempt keeps the score so far.
+                let in_run = match self.world.game_state() {
+                    GameState::Running => true,
+                    GameState::Paused => self.world.paused_state() == GameState::Running,
+                    _ => false,
+                };
+                if in_run && self.daily_scored {
+                    record_daily_result(&mut self.daily, self.daily_date, &self.world);
+                }
+                self.world.show_menu();
+                self.menus.set_screen(MenuScreen::Title);
+                self.menus.reset_pause();
+            },
+            MenuAction::Back => match self.menus.screen() {
+                MenuScreen::Title => midgar.set_should_exit(),
+                MenuScreen::Controls => self.menus.set_screen(MenuScreen::Settings),
+                _ => self.menus.set_screen(MenuScreen::Title),
+            },
+        }
+    }
+
+    // Applies edits to the config and assets. Anything that fails to load is
+    // reported and the game carries on with what it had.
+    fn reload_changed_files(&mut self, midgar: &Midgar, dt: f64) {
+        let changed = self.watcher.poll(dt);
+        if changed.is_empty() {
+            return;
+        }
+
+        if changed.contains(&self.config_path) {
+            match GameConfig::load(&self.config_path) {
+                Ok(mut config) => {
+                    // Everything is laid out around the screen size, so that
+                    // has to wait for a restart.
+                    if config.screen != self.world.config().screen {
+                        println!("Screen size changes apply after restarting the game");
+                        config.screen = self.world.config().screen;
+                    }
+                    self.world.set_config(config);
+                    println!("Reloaded {}", self.config_path.display());
+                },
+                Err(e) => println!("{}", e),
+            }
+        }
+
+        if changed.iter().any(|path| *path != self.config_path) {
+            match self.renderer.reload_assets(midgar) {
+                Ok(()) => println!("Reloaded assets"),
+                Err(e) => println!("Keeping old assets: {}", e),
+            }
+        }
+    }
+
+    // Gets ready for a run started from the menus. In the daily challenge
+    // this moves on to the new day's pipes if the date has changed, and uses
+    // up the day's scored attempt straight away, so quitting mid-run doesn't
+    // give another go.
+    fn prepare_run(&mut self) {
+        self.autopilot_used = self.autopilot;
+        self.daily_scored = false;
+        if self.world.mode() != GameMode::Daily {
+            return;
+        }
+
+        let today = Date::today();
+        if today != self.daily_date {
+            self.daily_date = today;
+            self.world.set_seed(rng::daily_seed(today));
+        }
+        // Only a run on your own uses up the day's attempt.
+        let scored = !self.autopilot && self.world.players().len() == 1;
+        if scored && self.daily.record(today, 0) {
+            self.daily_scored = true;
+            if let Err(e) = self.daily.save() {
+                println!("Could not save daily results: {}", e);
+            }
+        }
+    }
+
+    // Switches modes from the title screen, bringing up that mode's scores.
+    fn set_mode(&mut self, mode: GameMode) {
+        let previous_mode = self.world.mode();
+        if mode == GameMode::Daily {
+            // Everyone gets the same pipes on the same day.
+            self.daily_date = Date::today();
+            self.world.set_seed(rng::daily_seed(self.daily_date));
+            self.world.set_reuse_seed(true);
+        } else if previous_mode == GameMode::Daily {
+            self.world.set_seed(self.fixed_seed.unwrap_or_else(rng::random_seed));
+            self.world.set_reuse_seed(self.fixed_seed.is_some());
+        }
+        self.world.set_mode(mode);
+
+        self.high_scores = HighScoreTable::load(HighScoreTable::default_path(mode));
+        let best = self.best_score();
+        self.world.set_high_score(best);
+        self.menus.refresh_mode(mode);
+    }
+
+    // The score to beat in the current mode.
+    fn best_score(&self) -> u32 {
+        match self.world.mode() {
+            GameMode::Daily => self.daily.best(),
+            _ => self.high_scores.best(),
+        }
+    }
+
+    // Runs the evolution in place of the game: Escape quits, F speeds it up
+    // and S keeps the best bird so far.
+    fn step_evolution(&mut self, midgar: &mut Midgar, dt: f64) {
+        let evolution = self.evolution.as_mut().unwrap();
+
+        if controls::was_pressed(midgar, &self.settings.bindings, Action::Back) {
+            midgar.set_should_exit();
+        }
+        if midgar.input().was_key_pressed(KeyCode::F) {
+            self.fast_forward = !self.fast_forward;
+        }
+        if midgar.input().was_key_pressed(KeyCode::S) {
+            save_genome(evolution.best_genome());
+        }
+
+        let tick_dt = 1.0 / self.world.tick_rate() as f64;
+        let ticks_per_step = if self.fast_forward { FAST_FORWARD } else { 1 };
+        self.accumulator += dt.min(MAX_FRAME_TIME);
+        while self.accumulator >= tick_dt {
+            for _ in 0..ticks_per_step {
+                evolution.update(tick_dt as f32);
+            }
+            self.accumulator -= tick_dt;
+        }
+
+        let alpha = (self.accumulator / tick_dt) as f32;
+        self.renderer.render_evolution(midgar, alpha, evolution);
+    }
+
+    // Lets the autopilot show off a run while nobody is playing.
+    fn start_demo(&mut self) {
+        self.demo = true;
+        self.idle_time = 0.0;
+        self.world.start();
+    }
+
+    fn stop_demo(&mut self) {
+        self.demo = false;
+        self.world.show_menu();
+        self.menus.set_screen(MenuScreen::Title);
+        // Whatever the demo scored doesn't count.
+        let best = self.best_score();
+        self.world.set_high_score(best);
+    }
+
+
+    // Brings in the best run on the world's seed to race against, whenever
+    // the seed or mode changes. A run's own ghost waits for the next run.
+    fn refresh_ghost(&mut self) {
+        let key = (self.world.seed(), self.world.mode());
+        if self.ghost_key == Some(key) || self.world.is_game_over() {
+            return;
+        }
+        self.ghost_key = Some(key);
+
+        let path = Ghost::path(key.0, key.1);
+        let ghost = if path.exists() {
+            Ghost::load(&path).map_err(|e| println!("{}", e)).ok()
+        } else {
+            None
+        };
+        self.world.set_ghost(ghost);
+    }
+
+    fn set_scale(&mut self, midgar: &mut Midgar, scale: u32) {
+        let (width, height) = self.world.config().screen.window_size(scale);
+        midgar.graphics_mut().set_size(width, height);
+        self.settings.scale = scale;
+        self.save_settings();
+    }
+
+    fn save_settings(&mut self) {
+        self.menus.refresh_settings(&self.settings);
+        if let Err(e) = self.settings.save(Settings::default_path()) {
+            println!("Could not save settings: {}", e);
+        }
+    }
+
+    fn handle_events(&mut self) {
+        // Demos play silently behind the title screen.
+        let sound = self.settings.sound && !self.demo;
+        let mut restore_best = false;
+        for event in self.world.events() {
+            match *event {
+                GameEvent::Flapped if sound => self.sounds.flap.play(),
+                GameEvent::Scored if sound => self.sounds.coin.play(),
+                GameEvent::PickedUp(_) if sound => self.sounds.coin.play(),
+                GameEvent::ShieldBroken if sound => self.sounds.dead.play(),
+                GameEvent::Died if sound => self.sounds.dead.play(),
+                GameEvent::RunEnded => {
+                    self.menus.reset_game_over();
+                    match self.replay_player {
+                        Some(ref player) => {
+                            match player.replay().check(self.world.replay()) {
+                                Ok(()) => println!("Replay matched the recorded run"),
+                                Err(e) => println!("Replay diverged from the recorded run: {}", e),
+                            }
+                        },
+                        // Demo runs leave no trace.
+                        None if self.demo => {},
+                        // Nor do the autopilot's scores.
+                        None if self.autopilot_used => {
+                            save_replay(self.world.replay());
+                            restore_best = true;
+                        },
+                        // The tables are for one bird on its own, so runs
+                        // with more only get a replay.
+                        None if self.world.players().len() > 1 => {
+                            save_replay(self.world.replay());
+                            restore_best = true;
+                        },
+                        None => {
+                            save_replay(self.world.replay());
+                            let high_score = self.world.mode().keeps_high_scores() &&
+                                record_high_score(&mut self.high_scores, &self.world, &self.player_name);
+                            // Ghosts are only kept for seeds that get played
+                            // again: the daily challenge's, one fixed with
+                            // --seed, and those of high scores, which --seed
+                            // can bring back.
+                            let raced = self.world.reuses_seed() || high_score;
+                            if raced && save_ghost_if_best(&self.world) {
+                                // Race the new best from the next run on.
+                                self.ghost_key = None;
+                            }
+                            if self.daily_scored {
+                                record_daily_result(&mut self.daily, self.daily_date, &self.world);
+                            }
+                        },
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        // Neither the autopilot nor a flock can set a new best to beat.
+        if restore_best {
+            let best = self.best_score();
+            self.world.set_high_score(best);
+        }
+    }
+}
+
+// Adds the run to the high score table, returning whether it made the cut.
+fn record_high_score(high_scores: &mut HighScoreTable, world: &GameWorld, name: &Option<String>) -> bool {
+    let entry = HighScore {
+        score: world.score(),
+        date: Date::today(),
+        seed: world.seed(),
+        name: name.clone(),
+    };
+    let bottom = high_scores.scores().get(MAX_HIGH_SCORES - 1).cloned();
+    if high_scores.insert(entry).is_none() {
+        return false;
+    }
+    if let Err(e) = high_scores.save() {
+        println!("Could not save high scores: {}", e);
+    }
+
+    // The score pushed off the bottom takes its ghost with it, unless its
+    // seed is still being raced.
+    if let Some(dropped) = bottom {
+        let still_raced = dropped.seed == world.seed() ||
+            high_scores.scores().iter().any(|entry| entry.seed == dropped.seed);
+        if !still_raced {
+            remove_ghost(dropped.seed, world.mode());
+        }
+    }
+    true
+}
+
+// Saves the run's score as that of the day's scored attempt, if the world is
+// playing the daily challenge. The attempt itself is recorded when it starts.
+fn record_daily_result(daily: &mut DailyResults, date: Date, world: &GameWorld) {
+    if world.mode() == GameMode::Daily && daily.set_score(date, world.score()) {
+        if let Err(e) = daily.save() {
+            println!("Could not save daily results: {}", e);
+        }
+    }
+}
+
+// Keeps the run as the ghost for its seed if it beat the one there. Returns
+// whether it did.
+fn save_ghost_if_best(world: &GameWorld) -> bool {
+    let ghost = world.recorded_ghost();
+    let beaten = match world.ghost() {
+        Some(best) if best.seed == ghost.seed && best.mode == ghost.mode => ghost.score > best.score,
+        _ => ghost.score > 0,
+    };
+    if !beaten {
+        return false;
+    }
+
+    let path = Ghost::path(ghost.seed, ghost.mode);
+    match ghost.save(&path) {
+        Ok(()) => true,
+        Err(e) => {
+            println!("Could not save ghost to {}: {}", path.display(), e);
+            false
+        },
+    }
+}
+
+fn remove_ghost(seed: u64, mode: GameMode) {
+    let path = Ghost::path(seed, mode);
+    match fs::remove_file(&path) {
+        Ok(()) => {},
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => {},
+        Err(e) => println!("Could not remove ghost {}: {}", path.display(), e),
+    }
+}
+
+fn save_replay(replay: &Replay) {
+    let dir = paths::replay_dir();
+    let path = dir.join("last.zbr");
+    match fs::create_dir_all(&dir).and_then(|_| replay.save(&path)) {
+        Ok(()) => println!("Saved replay to {}", path.display()),
+        Err(e) => println!("Could not save replay to {}: {}", path.display(), e),
+    }
+}
+
+fn save_genome(genome: Option<&Genome>) {
+    let genome = match genome {
+        Some(genome) => genome,
+        None => {
+            println!("No generation has finished yet");
+            return;
+        },
+    };
+    let path = Genome::default_path();
+    match genome.save(&path) {
+        Ok(()) => println!("Saved the best genome to {}", path.display()),
+        Err(e) => println!("Could not save genome to {}: {}", path.display(), e),
+    }
+}
+
+// NOTE: Keep all window focus polling here.
+fn window_has_focus(midgar: &Midgar) -> bool {
+    midgar.input().has_focus()
+}
+
+fn read_menu_input(midgar: &Midgar, bindings: &Bindings, renderer: &GameRenderer) -> MenuInput {
+    let press = controls::was_pointer_pressed(midgar);
+    let release = controls::was_pointer_released(midgar);
+    // Only clicks count, so a pointer left resting on an entry doesn't keep
+    // the title screen from going idle.
+    let pointer = if press || release {
+        renderer.menu_entry_at(controls::pointer_position(midgar))
+    } else {
+        None
+    };
+    MenuInput {
+        up: controls::was_pressed(midgar, bindings, Action::Up),
+        down: controls::was_pressed(midgar, bindings, Action::Down),
+        confirm: controls::was_pressed(midgar, bindings, Action::Confirm),
+        back: controls::was_pressed(midgar, bindings, Action::Back),
+        press,
+        release,
+        pointer,
+    }
+}
+
+// Translate this frame's Midgar input into input for the game world.
+fn read_input(midgar: &Midgar, bindings: &Bindings) -> GameInput {
+    let mut input = GameInput::default();
+    for (player, flap) in input.flaps.iter_mut().enumerate() {
+        *flap = controls::was_pressed(midgar, bindings, Action::Flap(player));
+    }
+    input
+}
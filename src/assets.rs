@@ -0,0 +1,143 @@
+use std::fs::File;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use bmfont::{BMFont, OrdinateOrientation};
+use cgmath;
+use image;
+use midgar::{Midgar, Texture2d};
+use midgar::graphics::animation::{Animation, PlayMode};
+use midgar::graphics::sprite::Sprite;
+use midgar::graphics::texture::TextureRegion;
+
+use config::GAME_WIDTH;
+
+
+const TEXTURE_PATH: &str = "assets/texture.png";
+const TEXT_TEXTURE_PATH: &str = "assets/text.png";
+const SHADOW_TEXTURE_PATH: &str = "assets/shadow.png";
+const TEXT_FONT_PATH: &str = "assets/text.fnt";
+const SHADOW_FONT_PATH: &str = "assets/shadow.fnt";
+
+// Every character the game draws, checked against reloaded fonts so a font
+// missing one is rejected up front instead of failing mid-frame.
+const FONT_CHARACTERS: &str = "abcdefghijklmnopqrstuvwxyz0123456789 !.-<>/?";
+
+
+// Textures, regions and fonts used by the renderer. Loaded all at once so a
+// reload either fully succeeds or leaves the old set in place.
+pub struct Assets<'a> {
+    pub texture: Rc<Texture2d>,
+    // logoTexture: Rc<Texture2d>,
+    pub text_texture: Rc<Texture2d>,
+    pub shadow_texture: Rc<Texture2d>,
+
+    pub text_font: BMFont,
+    pub shadow_font: BMFont,
+
+    pub bird: TextureRegion,
+    pub bird_up: TextureRegion,
+    pub bird_down: TextureRegion,
+    pub bird_animation: Animation,
+
+    // TODO: Make these TextureRegions since we don't need to store state with them.
+    pub bg: Sprite<'a>,
+    pub grass: Sprite<'a>,
+    pub skull_up: Sprite<'a>,
+    pub skull_down: Sprite<'a>,
+    pub bar: TextureRegion,
+
+    // TextureRegion logo;
+    pub zb_logo: TextureRegion,
+    pub play_button_up: TextureRegion,
+    pub play_button_down: TextureRegion,
+}
+
+impl<'a> Assets<'a> {
+    // Files the assets are loaded from, for watching.
+    pub fn paths() -> Vec<PathBuf> {
+        [TEXTURE_PATH, TEXT_TEXTURE_PATH, SHADOW_TEXTURE_PATH, TEXT_FONT_PATH, SHADOW_FONT_PATH]
+            .iter()
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    pub fn load(midgar: &Midgar) -> Result<Self, String> {
+        let (screen_width, screen_height) = midgar.graphics().screen_size();
+        let game_height = screen_height as f32 / (screen_width as f32 / GAME_WIDTH);
+        let mid_point_y = (game_height / 2.0) as u32;
+
+        let texture = Rc::new(load_texture(midgar, TEXTURE_PATH, true)?);
+        let text_texture = Rc::new(load_texture(midgar, TEXT_TEXTURE_PATH, false)?);
+        let shadow_texture = Rc::new(load_texture(midgar, SHADOW_TEXTURE_PATH, false)?);
+
+        let text_font = load_font(TEXT_FONT_PATH)?;
+        let shadow_font = load_font(SHADOW_FONT_PATH)?;
+
+        // Load bird.
+        let bird = TextureRegion::with_sub_field(texture.clone(), (153, 116), (17, 12));
+        let bird_up = TextureRegion::with_sub_field(texture.clone(), (170, 116), (17, 12));
+        let bird_down = TextureRegion::with_sub_field(texture.clone(), (136, 116), (17, 12));
+
+        let mut bird_animation = Animation::new(0.06, &[bird_down.clone(), bird.clone(), bird_up.clone()]).unwrap();
+        bird_animation.play_mode = PlayMode::LoopPingPong;
+
+        // Load background.
+        let mut bg = Sprite::with_sub_field(texture.clone(), (0, 85), (136, 43));
+        bg.set_position(cgmath::vec2(0.0, mid_point_y as f32 - 66.0));
+
+        // Load grass.
+        let grass = Sprite::with_sub_field(texture.clone(), (0, 74), (143, 11));
+
+        // Load pipe.
+        let skull_up = Sprite::with_sub_field(texture.clone(), (192, 114), (24, 14));
+        let mut skull_down = Sprite::with_sub_field(texture.clone(), (192, 114), (24, 14));
+        skull_down.set_flip_y(true);
+        let bar = TextureRegion::with_sub_field(texture.clone(), (136, 109), (22, 3));
+
+        // Load menu.
+        let zb_logo = TextureRegion::with_sub_field(texture.clone(), (0, 49), (135, 24));
+        let play_button_up = TextureRegion::with_sub_field(texture.clone(), (0, 29), (29, 16));
+        let play_button_down = TextureRegion::with_sub_field(texture.clone(), (29, 29), (29, 16));
+
+        Ok(Assets {
+            texture,
+            text_texture,
+            shadow_texture,
+
+            text_font,
+            shadow_font,
+
+            bird,
+            bird_up,
+            bird_down,
+            bird_animation,
+
+            bg,
+            grass,
+            skull_up,
+            skull_down,
+            bar,
+
+            zb_logo,
+            play_button_up,
+            play_button_down,
+        })
+    }
+}
+
+fn load_texture(midgar: &Midgar, path: &str, generate_mipmaps: bool) -> Result<Texture2d, String> {
+    // Midgar panics on images it can't load, so make sure this one does
+    // first, to keep a bad edit from taking the game down.
+    image::open(path).map_err(|e| format!("Could not load texture {}: {}", path, e))?;
+    Ok(midgar.graphics().load_texture(path, generate_mipmaps))
+}
+
+fn load_font(path: &str) -> Result<BMFont, String> {
+    let file = File::open(path).map_err(|e| format!("Could not open font {}: {}", path, e))?;
+    let font = BMFont::new(file, OrdinateOrientation::TopToBottom)
+        .map_err(|e| format!("Could not load font {}: {:?}", path, e))?;
+    font.parse(FONT_CHARACTERS)
+        .map_err(|e| format!("Font {} can't draw the game's text: {:?}", path, e))?;
+    Ok(font)
+}
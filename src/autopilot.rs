@@ -0,0 +1,177 @@
+use std::collections::HashSet;
+
+use bird::{Bird, MAX_FALL_SPEED};
+use config::{BirdConfig, TICK_RATE};
+use input::GameInput;
+use world::{GameState, GameWorld};
+
+
+// How far ahead the bot plans, in ticks.
+const PLAN_TICKS: u32 = TICK_RATE * 3 / 2;
+// Ticks between the flaps the bot considers when planning. Coarser steps
+// plan faster but fly less tightly.
+const PLAN_STEP: u32 = 4;
+
+// Decides the next tick's flaps for every bird from the world as it stands,
+// the way a player watching the screen would.
+pub fn input(world: &GameWorld) -> GameInput {
+    let mut input = GameInput::new();
+    for (flap, player) in input.flaps.iter_mut().zip(world.players()) {
+        *flap = match world.game_state() {
+            // Set off straight away.
+            GameState::Ready => true,
+            GameState::Running => player.bird().is_alive() && Plan::new(world, player.bird()).should_flap(),
+            _ => false,
+        };
+    }
+    input
+}
+
+// A pipe's bars, as they stand.
+struct Bars {
+    left: f32,
+    right: f32,
+    // Bottom of the lower bar.
+    base: f32,
+    // The gap between the bars.
+    bottom: f32,
+    top: f32,
+}
+
+#[derive(Clone, Copy)]
+struct Flight {
+    // Height of the bird's bottom edge, as Bird keeps it.
+    y: f32,
+    velocity: f32,
+}
+
+// Works out whether the bird has to flap now to get through the next couple
+// of pipes, by trying out flights with flaps every few ticks. The pipes are
+// taken to keep their height and speed while it looks ahead.
+struct Plan {
+    config: BirdConfig,
+    bars: Vec<Bars>,
+    start: Flight,
+    // Where the bird's bounding circle sits relative to its position.
+    center_x: f32,
+    center_offset: f32,
+    scroll_speed: f32,
+    dt: f32,
+    ground: f32,
+    ceiling: f32,
+}
+
+impl Plan {
+    fn new(world: &GameWorld, bird: &Bird) -> Self {
+        let bars = world.scroller().pipes()
+            .map(|pipe| {
+                let position = pipe.position();
+                let bottom = position.y + pipe.height();
+                Bars {
+                    left: position.x,
+                    right: position.x + pipe.width() as f32,
+                    base: position.y,
+                    bottom,
+                    top: bottom + pipe.vertical_gap(),
+                }
+            })
+            .collect();
+        let (_, center) = bird.bounding_circle();
+
+        Plan {
+            config: world.config().bird,
+            bars,
+            start: Flight {
+                y: bird.position().y,
+                velocity: bird.velocity().y,
+            },
+            center_x: center.x,
+            center_offset: center.y - bird.position().y,
+            scroll_speed: world.difficulty().speed,
+            dt: world.game_speed() / world.tick_rate() as f32,
+            ground: world.ground_y(),
+            ceiling: world.game_size().1,
+        }
+    }
+
+    // Flaps only when there's no way through without flapping now.
+    fn should_flap(&self) -> bool {
+        let mut failed = HashSet::new();
+        match self.advance(self.start, false, 0, 1) {
+            Some(flight) => !self.survives(flight, 1, &mut failed),
+            None => true,
+        }
+    }
+
+    // Whether there's some way to fly on from `flight` at `tick` until the
+    // end of the plan. Dead ends are remembered in `failed` so they aren't
+    // explored again.
+    fn survives(&self, flight: Flight, tick: u32, failed: &mut HashSet<(u32, i32, i32)>) -> bool {
+        if tick >= PLAN_TICKS {
+            return true;
+        }
+        let key = (tick, flight.y.round() as i32, (flight.velocity / 5.0).round() as i32);
+        if failed.contains(&key) {
+            return false;
+        }
+
+        let survived = [false, true].iter().any(|&flap| {
+            match self.advance(flight, flap, tick, PLAN_STEP) {
+                Some(next) => self.survives(next, tick + PLAN_STEP, failed),
+                None => false,
+            }
+        });
+        if !survived {
+            failed.insert(key);
+        }
+        survived
+    }
+
+    // Flies the bird on for `ticks` ticks from `tick`, flapping first if
+    // `flap` is set. Nothing if it hits something on the way.
+    fn advance(&self, mut flight: Flight, flap: bool, tick: u32, ticks: u32) -> Option<Flight> {
+        let config = self.config;
+        if flap {
+            flight.velocity = config.flap_velocity;
+        }
+
+        for i in 1..=ticks {
+            // The same steps as Bird::update_running.
+            flight.velocity = (flight.velocity - config.gravity * self.dt).max(-MAX_FALL_SPEED);
+            if flight.y > self.ceiling + config.radius {
+                flight.y = self.ceiling + config.radius;
+                flight.velocity = 0.0;
+            }
+            flight.y += flight.velocity * self.dt;
+
+            let scrolled = self.scroll_speed * self.dt * (tick + i) as f32;
+            if self.hits(flight.y + self.center_offset, scrolled) {
+                return None;
+            }
+        }
+        Some(flight)
+    }
+
+    // Whether the bird's bounding circle, centred at height `center_y`,
+    // touches the ground or a pipe once the pipes have scrolled `scrolled`
+    // to the left.
+    fn hits(&self, center_y: f32, scrolled: f32) -> bool {
+        let radius = self.config.radius;
+        if center_y - radius <= self.ground {
+            return true;
+        }
+
+        self.bars.iter().any(|bars| {
+            let left = bars.left - scrolled;
+            let right = bars.right - scrolled;
+            let dx = (left - self.center_x).max(self.center_x - right).max(0.0);
+            if dx >= radius {
+                return false;
+            }
+            let lower_dy = (center_y - bars.bottom).max(bars.base - center_y).max(0.0);
+            let upper_dy = (bars.top - center_y).max(0.0);
+            dx * dx + lower_dy * lower_dy < radius * radius ||
+                dx * dx + upper_dy * upper_dy < radius * radius
+        })
+    }
+}
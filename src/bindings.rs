@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use toml;
+
+use input::MAX_PLAYERS;
+
+
+// Something the player can do, whichever control they do it with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    // Flaps the given player's bird.
+    Flap(usize),
+    Pause,
+    // Menu navigation.
+    Up,
+    Down,
+    Confirm,
+    Back,
+}
+
+impl Action {
+    pub const ALL: [Action; 9] = [
+        Action::Flap(0),
+        Action::Flap(1),
+        Action::Flap(2),
+        Action::Flap(3),
+        Action::Pause,
+        Action::Up,
+        Action::Down,
+        Action::Confirm,
+        Action::Back,
+    ];
+
+    // Name used in the settings file.
+    pub fn name(&self) -> String {
+        match *self {
+            Action::Flap(0) => "flap".to_string(),
+            Action::Flap(player) => format!("flap{}", player + 1),
+            Action::Pause => "pause".to_string(),
+            Action::Up => "up".to_string(),
+            Action::Down => "down".to_string(),
+            Action::Confirm => "confirm".to_string(),
+            Action::Back => "back".to_string(),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Action::ALL.iter().cloned().find(|action| action.name() == name)
+    }
+
+    // Whether the action works the menus.
+    pub fn is_menu(&self) -> bool {
+        matches!(*self, Action::Up | Action::Down | Action::Confirm | Action::Back)
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Action::Flap(player) => write!(f, "p{} flap", player + 1),
+            _ => write!(f, "{}", self.name()),
+        }
+    }
+}
+
+// A control an action can be bound to. Each is named the way the game's
+// window library names it, e.g. Key("Space"), Mouse("Left") or Pad("A"); the
+// game ignores names it doesn't know.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(String),
+    Mouse(String),
+    // A button on any gamepad.
+    Pad(String),
+}
+
+impl Binding {
+    pub fn key(name: &str) -> Self {
+        Binding::Key(name.to_string())
+    }
+
+    pub fn mouse(name: &str) -> Self {
+        Binding::Mouse(name.to_string())
+    }
+
+    pub fn pad(name: &str) -> Self {
+        Binding::Pad(name.to_string())
+    }
+
+    // Parses a binding as written in the settings file: "key:Space",
+    // "mouse:Left" or "pad:A".
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.splitn(2, ':');
+        let kind = parts.next()?;
+        let name = parts.next().filter(|name| !name.is_empty())?.to_string();
+        match kind {
+            "key" => Some(Binding::Key(name)),
+            "mouse" => Some(Binding::Mouse(name)),
+            "pad" => Some(Binding::Pad(name)),
+            _ => None,
+        }
+    }
+
+    // Whether the two are on the same device.
+    fn same_kind(&self, other: &Binding) -> bool {
+        matches!((self, other),
+                 (&Binding::Key(_), &Binding::Key(_)) |
+                 (&Binding::Mouse(_), &Binding::Mouse(_)) |
+                 (&Binding::Pad(_), &Binding::Pad(_)))
+    }
+
+    // Short name to show in menus.
+    pub fn label(&self) -> String {
+        match *self {
+            Binding::Key(ref name) => name.to_lowercase(),
+            Binding::Mouse(ref name) => format!("mouse {}", name.to_lowercase()),
+            Binding::Pad(ref name) => format!("pad {}", name.to_lowercase()),
+        }
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Binding::Key(ref name) => write!(f, "key:{}", name),
+            Binding::Mouse(ref name) => write!(f, "mouse:{}", name),
+            Binding::Pad(ref name) => write!(f, "pad:{}", name),
+        }
+    }
+}
+
+// Which controls do what. Saved with the settings as a table of action names
+// to lists of bindings:
+//
+//     [bindings]
+//     flap = ["key:Space", "mouse:Left", "pad:A"]
+//     pause = ["key:P", "key:Escape", "pad:Start"]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bindings {
+    bindings: HashMap<Action, Vec<Binding>>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        // Every player gets a key of their own; clicks and the gamepad flap
+        // for the first.
+        let player_keys = ["Space", "Up", "W", "L"];
+        let mut bindings = HashMap::new();
+        for (player, key) in player_keys.iter().enumerate().take(MAX_PLAYERS) {
+            let mut flap = vec![Binding::key(key)];
+            if player == 0 {
+                flap.push(Binding::mouse("Left"));
+                flap.push(Binding::pad("A"));
+            }
+            bindings.insert(Action::Flap(player), flap);
+        }
+        bindings.insert(Action::Pause, vec![Binding::key("P"), Binding::key("Escape"), Binding::pad("Start")]);
+        bindings.insert(Action::Up, vec![Binding::key("Up"), Binding::key("W"), Binding::pad("DPadUp")]);
+        bindings.insert(Action::Down, vec![Binding::key("Down"), Binding::key("S"), Binding::pad("DPadDown")]);
+        bindings.insert(Action::Confirm, vec![Binding::key("Return"), Binding::key("Space"), Binding::pad("A")]);
+        bindings.insert(Action::Back, vec![Binding::key("Escape"), Binding::pad("B")]);
+
+        Bindings {
+            bindings,
+        }
+    }
+}
+
+impl Bindings {
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    // Binds `binding` to `action` in place of whatever the action had on the
+    // same device, so rebinding a key keeps the gamepad button and so on.
+    // Menu actions can't share a control, since one that both moved through a
+    // menu and picked an entry would leave it unusable, so binding one to a
+    // control another already has fails.
+    pub fn bind(&mut self, action: Action, binding: Binding) -> Result<(), String> {
+        if let Some(other) = self.menu_clash(action, &binding) {
+            return Err(format!("{} is already bound to {}", binding, other));
+        }
+
+        let bindings = self.bindings.entry(action).or_default();
+        let first = bindings.iter().position(|old| old.same_kind(&binding));
+        bindings.retain(|old| !old.same_kind(&binding));
+        match first {
+            Some(i) => bindings.insert(i, binding),
+            None => bindings.push(binding),
+        }
+        Ok(())
+    }
+
+    // The other menu action `binding` is bound to, if `action` works the menus
+    // too.
+    fn menu_clash(&self, action: Action, binding: &Binding) -> Option<Action> {
+        if !action.is_menu() {
+            return None;
+        }
+        Action::ALL.iter().cloned()
+            .find(|&other| other != action && other.is_menu() && self.get(other).contains(binding))
+    }
+
+    // The first menu action `action` shares a control with, if any.
+    fn first_menu_clash(&self, action: Action) -> Option<Action> {
+        self.get(action).iter().filter_map(|binding| self.menu_clash(action, binding)).next()
+    }
+
+    // Shows the action's bindings for a menu entry, e.g. "p1 flap space/pad a".
+    pub fn label(&self, action: Action) -> String {
+        let bindings: Vec<String> = self.get(action).iter().map(Binding::label).collect();
+        format!("{} {}", action, bindings.join("/"))
+    }
+
+    // Reads the [bindings] table of the settings file. Actions that are
+    // missing keep their default bindings, and entries that don't parse are
+    // skipped. Menu actions left with no bindings, or sharing a control with
+    // another, get their defaults back so the menus can still be worked.
+    pub fn from_toml(table: &toml::value::Table) -> Self {
+        let mut bindings = Bindings::default();
+        for (name, value) in table {
+            let action = match Action::from_name(name) {
+                Some(action) => action,
+                None => {
+                    println!("Ignoring bindings for unknown action \"{}\"", name);
+                    continue;
+                },
+            };
+            let list = match value.as_array() {
+                Some(list) => list,
+                None => {
+                    println!("Ignoring bindings for {}, which aren't a list", name);
+                    continue;
+                },
+            };
+            let parsed = list.iter()
+                .filter_map(|value| {
+                    let binding = value.as_str().and_then(Binding::parse);
+                    if binding.is_none() {
+                        println!("Ignoring invalid binding {} for {}", value, name);
+                    }
+                    binding
+                })
+                .collect();
+            bindings.bindings.insert(action, parsed);
+        }
+
+        let defaults = Bindings::default();
+        let menu_actions: Vec<Action> = Action::ALL.iter().cloned().filter(Action::is_menu).collect();
+        for &action in &menu_actions {
+            if bindings.get(action).is_empty() {
+                println!("Nothing is bound to {}, using its default bindings", action);
+            } else if let Some(other) = bindings.first_menu_clash(action) {
+                println!("{} shares a control with {}, using its default bindings", action, other);
+            } else {
+                continue;
+            }
+            bindings.bindings.insert(action, defaults.get(action).to_vec());
+        }
+        // The defaults can still clash with what the others were bound to.
+        if menu_actions.iter().any(|&action| bindings.first_menu_clash(action).is_some()) {
+            println!("The menu actions still share controls, using all their default bindings");
+            for &action in &menu_actions {
+                bindings.bindings.insert(action, defaults.get(action).to_vec());
+            }
+        }
+        bindings
+    }
+
+    pub fn to_toml(&self) -> toml::value::Table {
+        let mut table = toml::value::Table::new();
+        for &action in &Action::ALL {
+            let list = self.get(action).iter()
+                .map(|binding| toml::Value::String(binding.to_string()))
+                .collect();
+            table.insert(action.name(), toml::Value::Array(list));
+        }
+        table
+    }
+}
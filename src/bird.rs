@@ -1,19 +1,19 @@
 use cgmath::{self, Vector2};
-use ears::AudioController;
-use midgar::{Midgar, KeyCode};
 use nalgebra;
 use ncollide::shape::Ball;
 
-use sounds::Sounds;
+use config::BirdConfig;
 //use units::WorldPosition;
 
 
-pub const BIRD_RADIUS: f32 = 6.5;
-pub const BIRD_GRAVITY: f32 = -460.0;
-
+// Fastest the bird can fall.
+pub const MAX_FALL_SPEED: f32 = 200.0;
 
 pub struct Bird {
     position: Vector2<f32>,
+    // State at the start of the current tick, used to interpolate rendering.
+    previous_position: Vector2<f32>,
+    previous_rotation: f32,
     velocity: Vector2<f32>,
     acceleration: Vector2<f32>,
 
@@ -27,14 +27,17 @@ pub struct Bird {
 
     bounding_circle: Ball<f32>,
     game_height: f32,
+    config: BirdConfig,
 }
 
 impl Bird {
-    pub fn new(x: f32, y: f32, width: u32, height: u32, game_height: f32) -> Self {
+    pub fn new(x: f32, y: f32, width: u32, height: u32, game_height: f32, config: BirdConfig) -> Self {
         Bird {
             position: cgmath::vec2(x, y),
+            previous_position: cgmath::vec2(x, y),
+            previous_rotation: 0.0,
             velocity: cgmath::vec2(0.0, 0.0),
-            acceleration: cgmath::vec2(0.0, BIRD_GRAVITY),
+            acceleration: cgmath::vec2(0.0, -config.gravity),
 
             rotation: 0.0,
             width: width,
@@ -44,32 +47,38 @@ impl Bird {
 
             is_alive: true,
 
-            bounding_circle: Ball::new(BIRD_RADIUS),
+            bounding_circle: Ball::new(config.radius),
             game_height: game_height,
+            config,
         }
     }
 
-    pub fn update_ready(&mut self, midgar: &Midgar, run_time: f32, sounds: &mut Sounds) {
+    // Must be called at the start of every tick, whether or not the bird is
+    // updated during it.
+    pub fn store_previous_state(&mut self) {
+        self.previous_position = self.position;
+        self.previous_rotation = self.rotation;
+    }
+
+    // Returns whether the bird flapped this update.
+    pub fn update_ready(&mut self, flap: bool, run_time: f32) -> bool {
         self.position.y = 2.0 * (7.0 * run_time).sin() + self.original_y;
-        if midgar.input().was_key_pressed(KeyCode::Space) {
-            self.on_click(sounds);
-        }
+        flap && self.on_click()
     }
 
-    pub fn update_running(&mut self, midgar: &Midgar, dt: f32, sounds: &mut Sounds) {
-        if midgar.input().was_key_pressed(KeyCode::Space) {
-            self.on_click(sounds);
-        }
+    // Returns whether the bird flapped this update.
+    pub fn update_running(&mut self, flap: bool, dt: f32) -> bool {
+        let flapped = flap && self.on_click();
 
         self.velocity += self.acceleration * dt;
 
-        if self.velocity.y < -200.0 {
-            self.velocity.y = -200.0;
+        if self.velocity.y < -MAX_FALL_SPEED {
+            self.velocity.y = -MAX_FALL_SPEED;
         }
 
         // Ceiling check.
-        if self.position.y > self.game_height + BIRD_RADIUS {
-            self.position.y = self.game_height + BIRD_RADIUS;
+        if self.position.y > self.game_height + self.config.radius {
+            self.position.y = self.game_height + self.config.radius;
             self.velocity.y = 0.0;
         }
 
@@ -91,15 +100,35 @@ impl Bird {
                 self.rotation = -90.0;
             }
         }
+
+        flapped
     }
 
-    fn on_click(&mut self, sounds: &mut Sounds) {
+    fn on_click(&mut self) -> bool {
         if self.is_alive {
-            self.velocity.y = 140.0;
-            sounds.flap.play();
+            self.velocity.y = self.config.flap_velocity;
+            true
+        } else {
+            false
         }
     }
 
+    // Applies new tuning values, keeping the bird where it is.
+    pub fn set_config(&mut self, config: BirdConfig) {
+        self.config = config;
+        self.bounding_circle = Ball::new(config.radius);
+        // Leave a bird that has stopped falling alone.
+        if self.acceleration.y < 0.0 {
+            self.acceleration.y = -config.gravity;
+        }
+    }
+
+    // Kicks the bird back up, for when it hits the ground in a mode it can't
+    // die in.
+    pub fn bounce(&mut self) {
+        self.velocity.y = self.config.flap_velocity;
+    }
+
     pub fn die(&mut self) {
         self.is_alive = false;
         self.velocity.y = 0.0;
@@ -116,8 +145,17 @@ impl Bird {
         self.velocity.x = 0.0;
         self.velocity.y = 0.0;
         self.acceleration.x = 0.0;
-        self.acceleration.y = BIRD_GRAVITY;
+        self.acceleration.y = -self.config.gravity;
         self.is_alive = true;
+        self.store_previous_state();
+    }
+
+    // Puts the bird at `x`, `y` moving vertically at `velocity_y`, as if it
+    // had been there all along. For setting up tests and tools.
+    pub fn place(&mut self, x: f32, y: f32, velocity_y: f32) {
+        self.position = cgmath::vec2(x, y);
+        self.velocity = cgmath::vec2(0.0, velocity_y);
+        self.store_previous_state();
     }
 
     pub fn is_falling(&self) -> bool {
@@ -132,6 +170,15 @@ impl Bird {
         self.position
     }
 
+    // Position blended between the previous and current tick.
+    pub fn interpolated_position(&self, alpha: f32) -> cgmath::Vector2<f32> {
+        self.previous_position + (self.position - self.previous_position) * alpha
+    }
+
+    pub fn velocity(&self) -> cgmath::Vector2<f32> {
+        self.velocity
+    }
+
     pub fn width(&self) -> u32 {
         self.width
     }
@@ -144,6 +191,10 @@ impl Bird {
         self.rotation
     }
 
+    pub fn interpolated_rotation(&self, alpha: f32) -> f32 {
+        self.previous_rotation + (self.rotation - self.previous_rotation) * alpha
+    }
+
     pub fn bounding_circle(&self) -> (&Ball<f32>, nalgebra::Vector2<f32>) {
         let bird_center = nalgebra::Vector2::new(self.position.x + 9.0, self.position.y + 6.0);
         (&self.bounding_circle, bird_center)
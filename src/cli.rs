@@ -0,0 +1,152 @@
+use std::env;
+use std::path::PathBuf;
+
+use config::GameConfig;
+use highscores::NAME_CHARACTERS;
+use input::MAX_PLAYERS;
+use mode::GameMode;
+
+
+pub const USAGE: &str = "\
+Usage: zombie_bird [options]
+
+Options:
+    --seed <n>         Use the given seed for every run's pipe layout
+    --name <name>      Name to put next to your high scores
+    --mode <mode>      Start in the given mode: classic, zen, time-attack,
+                       hardcore or daily
+    --players <n>      Number of birds, each on its own key (1 to 4)
+    --autopilot        Let the computer fly; F2 toggles it while playing
+    --replay <file>    Watch a recorded run
+    --verify <file>    Play a recorded run back without a window and check
+                       that it ends with the same score and collision
+    --soak <runs>      Fly the given number of autopilot runs without a
+                       window, checking the physics and that each run's
+                       replay plays back the same
+    --evolve           Watch a flock of birds learn to fly by neuroevolution;
+                       S saves the best bird's genome, F fast-forwards
+    --genome <file>    Start the flock from a saved genome (with --evolve)
+    --population <n>   Birds in each generation (with --evolve)
+    --config <file>    Load game tuning from the given file instead of
+                       assets/config.toml
+    -h, --help         Print this message";
+
+
+// Options given on the command line.
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    pub seed: Option<u64>,
+    pub name: Option<String>,
+    pub mode: Option<GameMode>,
+    pub players: Option<usize>,
+    pub autopilot: bool,
+    pub replay: Option<PathBuf>,
+    pub verify: Option<PathBuf>,
+    pub soak: Option<u32>,
+    pub evolve: bool,
+    pub genome: Option<PathBuf>,
+    pub population: Option<usize>,
+    pub config: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Options {
+    pub fn from_env() -> Result<Self, String> {
+        Options::parse(env::args().skip(1))
+    }
+
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Self, String> {
+        let mut options = Options::default();
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next().ok_or(format!("{} needs a value", name))
+            };
+
+            match arg.as_str() {
+                "--seed" => {
+                    let value = value("--seed")?;
+                    let seed = value.parse().map_err(|_| format!("Invalid seed \"{}\"", value))?;
+                    options.seed = Some(seed);
+                },
+                "--name" => {
+                    let value = value("--name")?;
+                    let name = value.to_lowercase();
+                    if !name.chars().all(|c| NAME_CHARACTERS.contains(c)) {
+                        return Err(format!("Invalid name \"{}\", use letters, digits, spaces and !.-", value));
+                    }
+                    options.name = Some(name);
+                },
+                "--mode" => {
+                    let value = value("--mode")?;
+                    let mode = GameMode::from_name(&value).ok_or(format!("Unknown mode \"{}\"", value))?;
+                    options.mode = Some(mode);
+                },
+                "--players" => {
+                    let value = value("--players")?;
+                    let players = value.parse().ok()
+                        .filter(|players| (1..=MAX_PLAYERS).contains(players))
+                        .ok_or(format!("Invalid number of players \"{}\", expected 1 to {}", value, MAX_PLAYERS))?;
+                    options.players = Some(players);
+                },
+                "--autopilot" => options.autopilot = true,
+                "--replay" => options.replay = Some(PathBuf::from(value("--replay")?)),
+                "--verify" => options.verify = Some(PathBuf::from(value("--verify")?)),
+                "--soak" => {
+                    let value = value("--soak")?;
+                    let runs = value.parse().ok()
+                        .filter(|&runs| runs > 0)
+                        .ok_or(format!("Invalid number of runs \"{}\"", value))?;
+                    options.soak = Some(runs);
+                },
+                "--evolve" => options.evolve = true,
+                "--genome" => options.genome = Some(PathBuf::from(value("--genome")?)),
+                "--population" => {
+                    let value = value("--population")?;
+                    let population = value.parse().ok()
+                        .filter(|&population| population > 0)
+                        .ok_or(format!("Invalid population \"{}\"", value))?;
+                    options.population = Some(population);
+                },
+                "--config" => options.config = Some(PathBuf::from(value("--config")?)),
+                "-h" | "--help" => options.help = true,
+                _ => return Err(format!("Unknown argument \"{}\"", arg)),
+            }
+        }
+
+        if options.replay.is_some() && options.seed.is_some() {
+            return Err("--seed can't be used with --replay".to_string());
+        }
+        if options.replay.is_some() && options.mode.is_some() {
+            return Err("--mode can't be used with --replay".to_string());
+        }
+        if options.replay.is_some() && options.players.is_some() {
+            return Err("--players can't be used with --replay".to_string());
+        }
+        if options.replay.is_some() && options.autopilot {
+            return Err("--autopilot can't be used with --replay".to_string());
+        }
+        if options.soak.is_some() && (options.replay.is_some() || options.verify.is_some()) {
+            return Err("--soak can't be used with --replay or --verify".to_string());
+        }
+        if options.evolve && (options.replay.is_some() || options.verify.is_some() || options.soak.is_some()) {
+            return Err("--evolve can't be used with --replay, --verify or --soak".to_string());
+        }
+        if !options.evolve && (options.genome.is_some() || options.population.is_some()) {
+            return Err("--genome and --population need --evolve".to_string());
+        }
+        if options.mode == Some(GameMode::Daily) && options.seed.is_some() {
+            return Err("--seed can't be used with the daily challenge".to_string());
+        }
+
+        Ok(options)
+    }
+
+    // Loads the game config asked for, or the default one.
+    pub fn load_config(&self) -> Result<GameConfig, String> {
+        match self.config {
+            Some(ref path) => GameConfig::load(path),
+            None => GameConfig::load_default(),
+        }
+    }
+}
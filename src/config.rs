@@ -1,2 +1,461 @@
-pub const SCREEN_SIZE: (u32, u32) = (136, 204);
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use toml;
+
+use difficulty::{Curve, DifficultyConfig, DifficultyDriver};
+
+
 pub const DEFAULT_SCALE: u32 = 2;
+// Width of the game in game pixels, which its art is drawn for. Its height
+// comes from the config, and the window is both times the scale.
+pub const GAME_WIDTH: f32 = 136.0;
+
+// The simulation always advances in steps of 1 / TICK_RATE seconds,
+// independent of the rendering frame rate.
+pub const TICK_RATE: u32 = 120;
+// Frame times above this are clamped so a long stall doesn't make the
+// simulation try to catch up with hundreds of ticks at once.
+pub const MAX_FRAME_TIME: f64 = 0.25;
+
+// How long the game over screen ignores input, so a late flap doesn't
+// restart the run by accident.
+pub const GAME_OVER_GRACE_TIME: f32 = 0.6;
+
+// Seconds counted down before a paused run picks up again.
+pub const RESUME_COUNTDOWN: f32 = 3.0;
+
+
+// Values that tune how the game plays, loaded from a TOML file so they can
+// be changed without recompiling:
+//
+//     [bird]
+//     gravity = 460.0
+//     radius = 6.5
+//     flap_velocity = 140.0
+//
+//     [scroll]
+//     speed = 59.0
+//     pipe_spacing = 49.0
+//     vertical_gap = 45.0
+//     height_variance = 90.0
+//
+//     [pipes]
+//     bob_chance = 0.15
+//     slide_distance = 30.0
+//
+//     [pickups]
+//     coin_chance = 0.2
+//
+//     [difficulty]
+//     driver = "score"
+//     speed = [[10.0, 1.0], [60.0, 1.3]]
+//
+//     [screen]
+//     height = 204
+//
+//     [medals]
+//     bronze = 10
+//     silver = 20
+//     gold = 30
+//     platinum = 40
+//
+// Anything left out keeps its default.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameConfig {
+    pub bird: BirdConfig,
+    pub scroll: ScrollConfig,
+    pub pipes: PipeConfig,
+    pub pickups: PickupConfig,
+    pub screen: ScreenConfig,
+    pub medals: MedalConfig,
+    pub difficulty: DifficultyConfig,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BirdConfig {
+    // Downwards acceleration, in game pixels per second squared.
+    pub gravity: f32,
+    pub radius: f32,
+    // Upwards speed a flap gives the bird.
+    pub flap_velocity: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScrollConfig {
+    // How fast the grass and pipes move left.
+    pub speed: f32,
+    // Horizontal space between one pipe and the next.
+    pub pipe_spacing: f32,
+    // Height of the opening the bird flies through.
+    pub vertical_gap: f32,
+    // How much the height of the opening varies from pipe to pipe.
+    pub height_variance: f32,
+}
+
+// How pipes that move get around. The chances are for a pipe picking each
+// behaviour when it comes back around, and are scaled by the difficulty's
+// moving_pipes curve.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PipeConfig {
+    // The gap drifts up and down.
+    pub bob_chance: f32,
+    pub bob_distance: f32,
+    // Seconds for one full drift up and back down.
+    pub bob_period: f32,
+    // The gap opens wider and closes back up.
+    pub breathe_chance: f32,
+    pub breathe_distance: f32,
+    pub breathe_period: f32,
+    // The gap jumps to a new height as the bird gets close.
+    pub slide_chance: f32,
+    pub slide_distance: f32,
+    // How far ahead of the bird a pipe starts to slide.
+    pub slide_trigger_distance: f32,
+    pub slide_time: f32,
+}
+
+// Pickups that can turn up between pipes. The chances are for each pipe
+// that comes around, and add up to at most 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PickupConfig {
+    pub radius: f32,
+    pub coin_chance: f32,
+    // Extra points for a coin.
+    pub coin_value: u32,
+    // A shield lets the bird fly through one pipe before it wears off.
+    pub shield_chance: f32,
+    pub shield_time: f32,
+    // Slow motion runs the game at slow_motion_speed for a while.
+    pub slow_motion_chance: f32,
+    pub slow_motion_time: f32,
+    pub slow_motion_speed: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScreenConfig {
+    // Height of the game in game pixels; it's always GAME_WIDTH wide.
+    pub height: u32,
+}
+
+impl ScreenConfig {
+    // Size of the game in game pixels.
+    pub fn game_size(&self) -> (f32, f32) {
+        (GAME_WIDTH, self.height as f32)
+    }
+
+    // Size of the window at the given scale.
+    pub fn window_size(&self, scale: u32) -> (u32, u32) {
+        (GAME_WIDTH as u32 * scale, self.height * scale)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MedalConfig {
+    // Scores needed for the bronze, silver, gold and platinum medals.
+    pub thresholds: [u32; 4],
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            bird: BirdConfig {
+                gravity: 460.0,
+                radius: 6.5,
+                flap_velocity: 140.0,
+            },
+            scroll: ScrollConfig {
+                speed: 59.0,
+                pipe_spacing: 49.0,
+                vertical_gap: 45.0,
+                height_variance: 90.0,
+            },
+            pipes: PipeConfig {
+                bob_chance: 0.15,
+                bob_distance: 12.0,
+                bob_period: 2.0,
+                breathe_chance: 0.1,
+                breathe_distance: 14.0,
+                breathe_period: 1.6,
+                slide_chance: 0.1,
+                slide_distance: 30.0,
+                slide_trigger_distance: 50.0,
+                slide_time: 0.4,
+            },
+            pickups: PickupConfig {
+                radius: 4.0,
+                coin_chance: 0.2,
+                coin_value: 2,
+                shield_chance: 0.05,
+                shield_time: 6.0,
+                slow_motion_chance: 0.05,
+                slow_motion_time: 4.0,
+                slow_motion_speed: 0.6,
+            },
+            screen: ScreenConfig {
+                height: 204,
+            },
+            medals: MedalConfig {
+                thresholds: [10, 20, 30, 40],
+            },
+            difficulty: DifficultyConfig::default(),
+        }
+    }
+}
+
+const MEDAL_NAMES: [&str; 4] = ["bronze", "silver", "gold", "platinum"];
+
+impl GameConfig {
+    pub fn default_path() -> PathBuf {
+        PathBuf::from("assets/config.toml")
+    }
+
+    // Loads the config at the default path, using the defaults if there
+    // isn't one.
+    pub fn load_default() -> Result<Self, String> {
+        let path = GameConfig::default_path();
+        if path.exists() {
+            GameConfig::load(path)
+        } else {
+            Ok(GameConfig::default())
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let mut text = String::new();
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut text))
+            .map_err(|e: io::Error| format!("Could not read config {}: {}", path.display(), e))?;
+        GameConfig::parse(&text)
+            .map_err(|e| format!("Invalid config {}: {}", path.display(), e))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let root: toml::Value = text.parse().map_err(|e: toml::de::Error| e.to_string())?;
+        let root = root.as_table().ok_or("expected a table")?;
+        let mut config = GameConfig::default();
+
+        for (name, section) in root {
+            let section = section.as_table()
+                .ok_or_else(|| format!("\"{}\" should be a section", name))?;
+            let mut reader = SectionReader {
+                name: name.as_str(),
+                section,
+                seen: Vec::new(),
+            };
+            match name.as_str() {
+                "bird" => {
+                    reader.read_f32("gravity", &mut config.bird.gravity)?;
+                    reader.read_f32("radius", &mut config.bird.radius)?;
+                    reader.read_f32("flap_velocity", &mut config.bird.flap_velocity)?;
+                },
+                "scroll" => {
+                    reader.read_f32("speed", &mut config.scroll.speed)?;
+                    reader.read_f32("pipe_spacing", &mut config.scroll.pipe_spacing)?;
+                    reader.read_f32("vertical_gap", &mut config.scroll.vertical_gap)?;
+                    reader.read_f32("height_variance", &mut config.scroll.height_variance)?;
+                },
+                "pipes" => {
+                    let pipes = &mut config.pipes;
+                    reader.read_f32("bob_chance", &mut pipes.bob_chance)?;
+                    reader.read_f32("bob_distance", &mut pipes.bob_distance)?;
+                    reader.read_f32("bob_period", &mut pipes.bob_period)?;
+                    reader.read_f32("breathe_chance", &mut pipes.breathe_chance)?;
+                    reader.read_f32("breathe_distance", &mut pipes.breathe_distance)?;
+                    reader.read_f32("breathe_period", &mut pipes.breathe_period)?;
+                    reader.read_f32("slide_chance", &mut pipes.slide_chance)?;
+                    reader.read_f32("slide_distance", &mut pipes.slide_distance)?;
+                    reader.read_f32("slide_trigger_distance", &mut pipes.slide_trigger_distance)?;
+                    reader.read_f32("slide_time", &mut pipes.slide_time)?;
+                },
+                "pickups" => {
+                    let pickups = &mut config.pickups;
+                    reader.read_f32("radius", &mut pickups.radius)?;
+                    reader.read_f32("coin_chance", &mut pickups.coin_chance)?;
+                    reader.read_u32("coin_value", &mut pickups.coin_value)?;
+                    reader.read_f32("shield_chance", &mut pickups.shield_chance)?;
+                    reader.read_f32("shield_time", &mut pickups.shield_time)?;
+                    reader.read_f32("slow_motion_chance", &mut pickups.slow_motion_chance)?;
+                    reader.read_f32("slow_motion_time", &mut pickups.slow_motion_time)?;
+                    reader.read_f32("slow_motion_speed", &mut pickups.slow_motion_speed)?;
+                },
+                "difficulty" => {
+                    reader.read_driver("driver", &mut config.difficulty.driver)?;
+                    reader.read_curve("speed", &mut config.difficulty.speed)?;
+                    reader.read_curve("vertical_gap", &mut config.difficulty.vertical_gap)?;
+                    reader.read_curve("height_variance", &mut config.difficulty.height_variance)?;
+                    reader.read_curve("moving_pipes", &mut config.difficulty.moving_pipes)?;
+                },
+                "screen" => {
+                    reader.read_u32("height", &mut config.screen.height)?;
+                },
+                "medals" => {
+                    for (medal, threshold) in MEDAL_NAMES.iter().zip(config.medals.thresholds.iter_mut()) {
+                        reader.read_u32(medal, threshold)?;
+                    }
+                },
+                _ => return Err(format!("unknown section [{}]", name)),
+            }
+            reader.check_unknown_keys()?;
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    // Checks the values make for a playable game.
+    pub fn validate(&self) -> Result<(), String> {
+        let positive = [
+            ("bird.gravity", self.bird.gravity),
+            ("bird.radius", self.bird.radius),
+            ("bird.flap_velocity", self.bird.flap_velocity),
+            ("scroll.speed", self.scroll.speed),
+            ("scroll.pipe_spacing", self.scroll.pipe_spacing),
+            ("scroll.vertical_gap", self.scroll.vertical_gap),
+            ("scroll.height_variance", self.scroll.height_variance),
+            ("pipes.bob_period", self.pipes.bob_period),
+            ("pipes.breathe_period", self.pipes.breathe_period),
+            ("pipes.slide_time", self.pipes.slide_time),
+            ("pickups.radius", self.pickups.radius),
+            ("pickups.shield_time", self.pickups.shield_time),
+            ("pickups.slow_motion_time", self.pickups.slow_motion_time),
+            ("pickups.slow_motion_speed", self.pickups.slow_motion_speed),
+        ];
+        for &(key, value) in &positive {
+            if !(value > 0.0 && value.is_finite()) {
+                return Err(format!("{} must be a positive number, got {}", key, value));
+            }
+        }
+
+        let not_negative = [
+            ("pipes.bob_chance", self.pipes.bob_chance),
+            ("pipes.bob_distance", self.pipes.bob_distance),
+            ("pipes.breathe_chance", self.pipes.breathe_chance),
+            ("pipes.breathe_distance", self.pipes.breathe_distance),
+            ("pipes.slide_chance", self.pipes.slide_chance),
+            ("pipes.slide_distance", self.pipes.slide_distance),
+            ("pipes.slide_trigger_distance", self.pipes.slide_trigger_distance),
+            ("pickups.coin_chance", self.pickups.coin_chance),
+            ("pickups.shield_chance", self.pickups.shield_chance),
+            ("pickups.slow_motion_chance", self.pickups.slow_motion_chance),
+        ];
+        for &(key, value) in &not_negative {
+            if !(value >= 0.0 && value.is_finite()) {
+                return Err(format!("{} must not be negative, got {}", key, value));
+            }
+        }
+        let total_chance = self.pipes.bob_chance + self.pipes.breathe_chance + self.pipes.slide_chance;
+        if total_chance > 1.0 {
+            return Err(format!("pipe behaviour chances add up to {}, more than 1", total_chance));
+        }
+        let total_chance = self.pickups.coin_chance + self.pickups.shield_chance +
+            self.pickups.slow_motion_chance;
+        if total_chance > 1.0 {
+            return Err(format!("pickup chances add up to {}, more than 1", total_chance));
+        }
+
+        self.difficulty.validate()?;
+
+        // The gap can only shrink as far as the difficulty curve takes it.
+        let narrowest = self.difficulty.vertical_gap.points().iter()
+            .map(|&(_, multiplier)| self.scroll.vertical_gap * multiplier)
+            .fold(self.scroll.vertical_gap, f32::min);
+        if self.bird.radius * 2.0 >= narrowest {
+            return Err(format!("scroll.vertical_gap ({}) must stay wider than the bird ({})",
+                               narrowest, self.bird.radius * 2.0));
+        }
+        if self.screen.height == 0 {
+            return Err("screen.height must not be zero".to_string());
+        }
+        if self.medals.thresholds.windows(2).any(|pair| pair[0] > pair[1]) {
+            return Err(format!("medal thresholds must not decrease, got {:?}",
+                               self.medals.thresholds));
+        }
+
+        Ok(())
+    }
+}
+
+// Reads values out of one section of the config, remembering which keys it
+// has seen so typos can be reported.
+struct SectionReader<'a> {
+    name: &'a str,
+    section: &'a toml::value::Table,
+    seen: Vec<&'a str>,
+}
+
+impl<'a> SectionReader<'a> {
+    fn read_f32(&mut self, key: &'a str, value: &mut f32) -> Result<(), String> {
+        self.seen.push(key);
+        if let Some(number) = self.section.get(key) {
+            *value = as_f32(number)
+                .ok_or_else(|| format!("{}.{} should be a number", self.name, key))?;
+        }
+        Ok(())
+    }
+
+    fn read_u32(&mut self, key: &'a str, value: &mut u32) -> Result<(), String> {
+        self.seen.push(key);
+        match self.section.get(key) {
+            Some(&toml::Value::Integer(number)) if (0..=u32::MAX as i64).contains(&number) => {
+                *value = number as u32;
+            },
+            Some(_) => return Err(format!("{}.{} should be a whole number", self.name, key)),
+            None => {},
+        }
+        Ok(())
+    }
+
+    fn read_driver(&mut self, key: &'a str, value: &mut DifficultyDriver) -> Result<(), String> {
+        self.seen.push(key);
+        match self.section.get(key) {
+            Some(toml::Value::String(name)) => {
+                *value = DifficultyDriver::from_name(name)
+                    .ok_or_else(|| format!("{}.{} should be \"score\" or \"time\", got \"{}\"",
+                                           self.name, key, name))?;
+            },
+            Some(_) => return Err(format!("{}.{} should be a string", self.name, key)),
+            None => {},
+        }
+        Ok(())
+    }
+
+    // Curves are written as a list of [x, y] points.
+    fn read_curve(&mut self, key: &'a str, value: &mut Curve) -> Result<(), String> {
+        self.seen.push(key);
+        let points = match self.section.get(key) {
+            Some(toml::Value::Array(points)) => points,
+            Some(_) => return Err(format!("{}.{} should be a list of [x, y] points", self.name, key)),
+            None => return Ok(()),
+        };
+
+        let points = points.iter()
+            .map(|point| match point.as_array().map(Vec::as_slice) {
+                Some([x, y]) => match (as_f32(x), as_f32(y)) {
+                    (Some(x), Some(y)) => Ok((x, y)),
+                    _ => Err(format!("{}.{} points should hold two numbers", self.name, key)),
+                },
+                _ => Err(format!("{}.{} should be a list of [x, y] points", self.name, key)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        *value = Curve::new(points).map_err(|e| format!("{}.{} {}", self.name, key, e))?;
+        Ok(())
+    }
+
+    fn check_unknown_keys(&self) -> Result<(), String> {
+        match self.section.keys().find(|key| !self.seen.contains(&key.as_str())) {
+            Some(key) => Err(format!("unknown key {}.{}", self.name, key)),
+            None => Ok(()),
+        }
+    }
+}
+
+fn as_f32(value: &toml::Value) -> Option<f32> {
+    match *value {
+        toml::Value::Float(number) => Some(number as f32),
+        toml::Value::Integer(number) => Some(number as f32),
+        _ => None,
+    }
+}
@@ -0,0 +1,107 @@
+use midgar::{Button, KeyCode, Midgar, MouseButton};
+
+use bindings::{Action, Binding, Bindings};
+use config::GAME_WIDTH;
+
+
+// Every control that can be bound, by the name bindings use for it.
+const KEYS: &[(&str, KeyCode)] = &[
+    ("Space", KeyCode::Space), ("Return", KeyCode::Return), ("Escape", KeyCode::Escape),
+    ("Tab", KeyCode::Tab), ("Backspace", KeyCode::Backspace),
+    ("LShift", KeyCode::LShift), ("RShift", KeyCode::RShift),
+    ("LCtrl", KeyCode::LCtrl), ("RCtrl", KeyCode::RCtrl),
+    ("Up", KeyCode::Up), ("Down", KeyCode::Down), ("Left", KeyCode::Left), ("Right", KeyCode::Right),
+    ("A", KeyCode::A), ("B", KeyCode::B), ("C", KeyCode::C), ("D", KeyCode::D), ("E", KeyCode::E),
+    ("F", KeyCode::F), ("G", KeyCode::G), ("H", KeyCode::H), ("I", KeyCode::I), ("J", KeyCode::J),
+    ("K", KeyCode::K), ("L", KeyCode::L), ("M", KeyCode::M), ("N", KeyCode::N), ("O", KeyCode::O),
+    ("P", KeyCode::P), ("Q", KeyCode::Q), ("R", KeyCode::R), ("S", KeyCode::S), ("T", KeyCode::T),
+    ("U", KeyCode::U), ("V", KeyCode::V), ("W", KeyCode::W), ("X", KeyCode::X), ("Y", KeyCode::Y),
+    ("Z", KeyCode::Z),
+    ("Num0", KeyCode::Num0), ("Num1", KeyCode::Num1), ("Num2", KeyCode::Num2), ("Num3", KeyCode::Num3),
+    ("Num4", KeyCode::Num4), ("Num5", KeyCode::Num5), ("Num6", KeyCode::Num6), ("Num7", KeyCode::Num7),
+    ("Num8", KeyCode::Num8), ("Num9", KeyCode::Num9),
+];
+const MOUSE_BUTTONS: &[(&str, MouseButton)] = &[
+    ("Left", MouseButton::Left), ("Middle", MouseButton::Middle), ("Right", MouseButton::Right),
+];
+const PAD_BUTTONS: &[(&str, Button)] = &[
+    ("A", Button::A), ("B", Button::B), ("X", Button::X), ("Y", Button::Y),
+    ("Back", Button::Back), ("Start", Button::Start),
+    ("LeftShoulder", Button::LeftShoulder), ("RightShoulder", Button::RightShoulder),
+    ("LeftStick", Button::LeftStick), ("RightStick", Button::RightStick),
+    ("DPadUp", Button::DPadUp), ("DPadDown", Button::DPadDown),
+    ("DPadLeft", Button::DPadLeft), ("DPadRight", Button::DPadRight),
+];
+
+fn lookup<T: Copy>(table: &[(&str, T)], name: &str) -> Option<T> {
+    table.iter().find(|&&(known, _)| known == name).map(|&(_, control)| control)
+}
+
+// NOTE: Keep all polling of keys and buttons here.
+
+// Whether a control bound to `action` was pressed this frame.
+pub fn was_pressed(midgar: &Midgar, bindings: &Bindings, action: Action) -> bool {
+    bindings.get(action).iter().any(|binding| was_binding_pressed(midgar, binding))
+}
+
+fn was_binding_pressed(midgar: &Midgar, binding: &Binding) -> bool {
+    let input = midgar.input();
+    match *binding {
+        Binding::Key(ref name) => lookup(KEYS, name)
+            .map_or(false, |key| input.was_key_pressed(key)),
+        Binding::Mouse(ref name) => lookup(MOUSE_BUTTONS, name)
+            .map_or(false, |button| input.was_mouse_pressed(button)),
+        Binding::Pad(ref name) => lookup(PAD_BUTTONS, name)
+            .map_or(false, |button| input.was_button_pressed(button)),
+    }
+}
+
+// The first control pressed this frame, if any, for binding to an action.
+pub fn pressed_binding(midgar: &Midgar) -> Option<Binding> {
+    let input = midgar.input();
+    let key = KEYS.iter()
+        .find(|&&(_, key)| input.was_key_pressed(key))
+        .map(|&(name, _)| Binding::key(name));
+    let mouse = || MOUSE_BUTTONS.iter()
+        .find(|&&(_, button)| input.was_mouse_pressed(button))
+        .map(|&(name, _)| Binding::mouse(name));
+    let pad = || PAD_BUTTONS.iter()
+        .find(|&&(_, button)| input.was_button_pressed(button))
+        .map(|&(name, _)| Binding::pad(name));
+    key.or_else(mouse).or_else(pad)
+}
+
+// Whether the pointer went down or came up this frame.
+pub fn was_pointer_pressed(midgar: &Midgar) -> bool {
+    midgar.input().was_mouse_pressed(MouseButton::Left)
+}
+
+pub fn was_pointer_released(midgar: &Midgar) -> bool {
+    midgar.input().was_mouse_released(MouseButton::Left)
+}
+
+// Where the pointer is, in game coordinates. The game is GAME_WIDTH wide and
+// scaled up to fill the window, as the renderer's projection lays it out, and
+// its y axis points up where the window's points down.
+pub fn pointer_position(midgar: &Midgar) -> (f32, f32) {
+    let (x, y) = midgar.input().mouse_pos();
+    let (screen_width, screen_height) = midgar.graphics().screen_size();
+    let scale = GAME_WIDTH / screen_width as f32;
+    (x as f32 * scale, (screen_height as f32 - y as f32) * scale)
+}
+
+// Warns about bindings to controls the game doesn't know, which do nothing.
+pub fn check(bindings: &Bindings) {
+    for &action in &Action::ALL {
+        for binding in bindings.get(action) {
+            let known = match *binding {
+                Binding::Key(ref name) => lookup(KEYS, name).is_some(),
+                Binding::Mouse(ref name) => lookup(MOUSE_BUTTONS, name).is_some(),
+                Binding::Pad(ref name) => lookup(PAD_BUTTONS, name).is_some(),
+            };
+            if !known {
+                println!("Ignoring unknown control {} bound to {}", binding, action);
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use std::cmp::Reverse;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use toml;
+
+use date::Date;
+use paths;
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DailyResult {
+    pub date: Date,
+    pub score: u32,
+}
+
+// The scored attempt of every daily challenge played, newest first, backed by
+// a TOML file:
+//
+//     [[days]]
+//     date = "2016-11-02"
+//     score = 12
+pub struct DailyResults {
+    path: PathBuf,
+    days: Vec<DailyResult>,
+}
+
+impl DailyResults {
+    pub fn default_path() -> PathBuf {
+        paths::data_dir().join("daily.toml")
+    }
+
+    // Loads the results at `path`. A missing file gives no results, and a
+    // corrupt one is moved aside so the next save doesn't clobber it.
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let mut results = DailyResults {
+            path,
+            days: Vec::new(),
+        };
+
+        let mut text = String::new();
+        match File::open(&results.path).and_then(|mut file| file.read_to_string(&mut text)) {
+            Ok(_) => {},
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return results,
+            Err(e) => {
+                println!("Could not read daily results from {}: {}", results.path.display(), e);
+                return results;
+            },
+        }
+
+        match parse_days(&text) {
+            Ok(days) => results.days = days,
+            Err(e) => {
+                let backup = results.path.with_extension("toml.corrupt");
+                println!("Daily results in {} are corrupt ({}), moving them to {}",
+                         results.path.display(), e, backup.display());
+                if let Err(e) = fs::rename(&results.path, &backup) {
+                    println!("Could not move corrupt daily results: {}", e);
+                }
+            },
+        }
+
+        results
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        paths::write_atomically(&self.path, self.to_toml().to_string().as_bytes())
+    }
+
+    // Whether the scored attempt for `date` has been used up.
+    pub fn has_played(&self, date: Date) -> bool {
+        self.result(date).is_some()
+    }
+
+    pub fn result(&self, date: Date) -> Option<&DailyResult> {
+        self.days.iter().find(|result| result.date == date)
+    }
+
+    // Records the day's scored attempt. Returns false, leaving the results
+    // alone, if that day already has one.
+    pub fn record(&mut self, date: Date, score: u32) -> bool {
+        if self.has_played(date) {
+            return false;
+        }
+
+        let position = self.days.iter()
+            .position(|result| result.date < date)
+            .unwrap_or(self.days.len());
+        self.days.insert(position, DailyResult { date, score });
+        true
+    }
+
+    // Changes the score of the day's attempt, once it's been recorded.
+    // Returns whether there was one to change.
+    pub fn set_score(&mut self, date: Date, score: u32) -> bool {
+        match self.days.iter_mut().find(|result| result.date == date) {
+            Some(result) => {
+                result.score = score;
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn best(&self) -> u32 {
+        self.days.iter().map(|result| result.score).max().unwrap_or(0)
+    }
+
+    pub fn days(&self) -> &[DailyResult] {
+        &self.days
+    }
+
+    fn to_toml(&self) -> toml::Value {
+        let days = self.days.iter().map(|result| {
+            let mut table = toml::value::Table::new();
+            table.insert("date".to_string(), toml::Value::String(result.date.to_string()));
+            table.insert("score".to_string(), toml::Value::Integer(result.score as i64));
+            toml::Value::Table(table)
+        }).collect();
+
+        let mut root = toml::value::Table::new();
+        root.insert("days".to_string(), toml::Value::Array(days));
+        toml::Value::Table(root)
+    }
+}
+
+fn parse_days(text: &str) -> Result<Vec<DailyResult>, String> {
+    let root: toml::Value = text.parse().map_err(|e: toml::de::Error| e.to_string())?;
+    let entries = match root.get("days") {
+        Some(toml::Value::Array(entries)) => entries,
+        Some(_) => return Err("\"days\" is not an array".to_string()),
+        None => return Ok(Vec::new()),
+    };
+
+    let mut days = entries.iter()
+        .map(parse_day)
+        .collect::<Result<Vec<_>, _>>()?;
+    days.sort_by_key(|result| Reverse(result.date));
+    days.dedup_by_key(|result| result.date);
+
+    Ok(days)
+}
+
+fn parse_day(value: &toml::Value) -> Result<DailyResult, String> {
+    let date = value.get("date")
+        .and_then(toml::Value::as_str)
+        .ok_or("daily result without a date")?
+        .parse()?;
+    let score = value.get("score")
+        .and_then(toml::Value::as_integer)
+        .filter(|&score| score >= 0)
+        .ok_or("daily result without a valid score")?;
+
+    Ok(DailyResult {
+        date,
+        score: score as u32,
+    })
+}
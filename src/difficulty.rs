@@ -0,0 +1,146 @@
+use config::GameConfig;
+
+
+// What moves a run along its difficulty curves.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DifficultyDriver {
+    Score,
+    // Seconds since the bird first flapped.
+    Time,
+}
+
+impl DifficultyDriver {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            DifficultyDriver::Score => "score",
+            DifficultyDriver::Time => "time",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "score" => Some(DifficultyDriver::Score),
+            "time" => Some(DifficultyDriver::Time),
+            _ => None,
+        }
+    }
+}
+
+// A value that changes over a run, given as points joined by straight lines.
+// Before the first point and after the last the curve stays flat.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Curve {
+    points: Vec<(f32, f32)>,
+}
+
+impl Curve {
+    pub fn new(points: Vec<(f32, f32)>) -> Result<Self, String> {
+        if points.is_empty() {
+            return Err("needs at least one point".to_string());
+        }
+        if points.windows(2).any(|pair| pair[0].0 >= pair[1].0) {
+            return Err("points must be in increasing order".to_string());
+        }
+        Ok(Curve { points })
+    }
+
+    pub fn flat(value: f32) -> Self {
+        Curve { points: vec![(0.0, value)] }
+    }
+
+    pub fn at(&self, x: f32) -> f32 {
+        let first = self.points[0];
+        if x <= first.0 {
+            return first.1;
+        }
+
+        for pair in self.points.windows(2) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            if x <= x1 {
+                return y0 + (y1 - y0) * (x - x0) / (x1 - x0);
+            }
+        }
+        self.points[self.points.len() - 1].1
+    }
+
+    pub fn points(&self) -> &[(f32, f32)] {
+        &self.points
+    }
+}
+
+// How a run gets harder. Each curve gives a multiplier for the matching
+// [scroll] value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DifficultyConfig {
+    pub driver: DifficultyDriver,
+    pub speed: Curve,
+    pub vertical_gap: Curve,
+    pub height_variance: Curve,
+    // Scales the chance of a pipe moving. Unlike the others this can be 0.
+    pub moving_pipes: Curve,
+}
+
+impl Default for DifficultyConfig {
+    fn default() -> Self {
+        DifficultyConfig {
+            driver: DifficultyDriver::Score,
+            speed: Curve { points: vec![(10.0, 1.0), (60.0, 1.3)] },
+            vertical_gap: Curve { points: vec![(10.0, 1.0), (60.0, 0.85)] },
+            height_variance: Curve { points: vec![(10.0, 1.0), (60.0, 1.2)] },
+            moving_pipes: Curve { points: vec![(10.0, 0.0), (40.0, 1.0)] },
+        }
+    }
+}
+
+impl DifficultyConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        let curves = [
+            ("difficulty.speed", &self.speed),
+            ("difficulty.vertical_gap", &self.vertical_gap),
+            ("difficulty.height_variance", &self.height_variance),
+        ];
+        for &(key, curve) in &curves {
+            let bad_point = curve.points.iter().find(|&&(_, value)| !value.is_finite() || value <= 0.0);
+            if let Some(&(_, value)) = bad_point {
+                return Err(format!("{} multipliers must be positive, got {}", key, value));
+            }
+        }
+
+        let bad_point = self.moving_pipes.points.iter().find(|&&(_, value)| !value.is_finite() || value < 0.0);
+        if let Some(&(_, value)) = bad_point {
+            return Err(format!("difficulty.moving_pipes multipliers must not be negative, got {}", value));
+        }
+        Ok(())
+    }
+}
+
+// Scroll parameters at one point in a run, used whenever a pipe is reset.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Difficulty {
+    pub speed: f32,
+    pub vertical_gap: f32,
+    pub height_variance: f32,
+    pub bob_chance: f32,
+    pub breathe_chance: f32,
+    pub slide_chance: f32,
+}
+
+impl Difficulty {
+    pub fn at(config: &GameConfig, score: u32, seconds: f32) -> Self {
+        let curves = &config.difficulty;
+        let x = match curves.driver {
+            DifficultyDriver::Score => score as f32,
+            DifficultyDriver::Time => seconds,
+        };
+
+        let moving = curves.moving_pipes.at(x);
+        Difficulty {
+            speed: config.scroll.speed * curves.speed.at(x),
+            vertical_gap: config.scroll.vertical_gap * curves.vertical_gap.at(x),
+            height_variance: config.scroll.height_variance * curves.height_variance.at(x),
+            bob_chance: config.pipes.bob_chance * moving,
+            breathe_chance: config.pipes.breathe_chance * moving,
+            slide_chance: config.pipes.slide_chance * moving,
+        }
+    }
+}
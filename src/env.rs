@@ -0,0 +1,360 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+use bird::Bird;
+use config::{GameConfig, TICK_RATE};
+use headless;
+use input::GameInput;
+use mode::GameMode;
+use scroll_handler::{Pipe, ScrollHandler};
+use world::GameWorld;
+
+
+// What an agent sees after each step; see ObservationConfig for the layout.
+pub type Observation = Vec<f32>;
+
+// What an agent can do on a step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    Idle,
+    Flap,
+}
+
+// What goes into an observation, in this order:
+//
+// * For each of the next `pipes` pipes the bird hasn't got past, nearest
+//   first: how far ahead its left edge is, and how far above the bird's
+//   center the bottom and top of its gap are. Missing pipes read as a
+//   screen's width ahead and level with the bird.
+// * If `height` is set, how high the bird's center is above the ground.
+// * If `velocity` is set, how fast the bird is rising (or falling, if
+//   negative).
+//
+// Distances are in game pixels and velocity in pixels per second, unless
+// `normalize` is set, in which case distances are in screen heights and
+// velocity in flaps' worth.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ObservationConfig {
+    pub pipes: usize,
+    pub height: bool,
+    pub velocity: bool,
+    pub normalize: bool,
+}
+
+impl ObservationConfig {
+    // Number of values in an observation.
+    pub fn size(&self) -> usize {
+        self.pipes * 3 + self.height as usize + self.velocity as usize
+    }
+
+    // What `bird` sees, flying among `scroller`'s pipes in a world of the
+    // given config and size.
+    pub fn observe(&self, bird: &Bird, scroller: &ScrollHandler, config: &GameConfig, game_size: (f32, f32),
+                   ground_y: f32) -> Observation {
+        let (game_width, game_height) = game_size;
+        let (distance_scale, velocity_scale) = if self.normalize {
+            (1.0 / game_height, 1.0 / config.bird.flap_velocity)
+        } else {
+            (1.0, 1.0)
+        };
+
+        let (_, center) = bird.bounding_circle();
+        let radius = config.bird.radius;
+        let mut pipes: Vec<&Pipe> = scroller.pipes()
+            .filter(|pipe| pipe.position().x + pipe.width() as f32 > center.x - radius)
+            .collect();
+        pipes.sort_by(|a, b| a.position().x.partial_cmp(&b.position().x).unwrap());
+
+        let mut observation = Vec::with_capacity(self.size());
+        for i in 0..self.pipes {
+            let (ahead, bottom, top) = match pipes.get(i) {
+                Some(pipe) => {
+                    let bottom = pipe.position().y + pipe.height();
+                    (pipe.position().x - center.x, bottom - center.y, bottom + pipe.vertical_gap() - center.y)
+                },
+                None => (game_width, 0.0, 0.0),
+            };
+            observation.extend_from_slice(&[ahead * distance_scale, bottom * distance_scale, top * distance_scale]);
+        }
+        if self.height {
+            observation.push((center.y - ground_y) * distance_scale);
+        }
+        if self.velocity {
+            observation.push(bird.velocity().y * velocity_scale);
+        }
+        observation
+    }
+}
+
+impl Default for ObservationConfig {
+    fn default() -> Self {
+        ObservationConfig {
+            pipes: 2,
+            height: true,
+            velocity: true,
+            normalize: true,
+        }
+    }
+}
+
+// What a step is worth.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rewards {
+    // For each step the bird lives through.
+    pub alive: f32,
+    // For each point scored.
+    pub score: f32,
+    // For dying.
+    pub death: f32,
+}
+
+impl Default for Rewards {
+    fn default() -> Self {
+        Rewards {
+            alive: 0.01,
+            score: 1.0,
+            death: -1.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EnvConfig {
+    pub game: GameConfig,
+    pub mode: GameMode,
+    pub observation: ObservationConfig,
+    pub rewards: Rewards,
+    // Ticks each step lasts. The action is taken on the first of them.
+    pub frame_skip: u32,
+    // Steps after which an episode is cut short, if any.
+    pub max_steps: Option<u32>,
+}
+
+impl Default for EnvConfig {
+    fn default() -> Self {
+        EnvConfig {
+            game: GameConfig::default(),
+            mode: GameMode::Classic,
+            observation: ObservationConfig::default(),
+            rewards: Rewards::default(),
+            frame_skip: 1,
+            max_steps: None,
+        }
+    }
+}
+
+// A single-bird game for an agent to play, one step at a time, with no
+// window and no real time. Episodes run from the first flap until the bird
+// dies, the mode ends the run or max_steps is up.
+pub struct Env {
+    world: GameWorld,
+    config: EnvConfig,
+    // Steps taken this episode.
+    steps: u32,
+}
+
+impl Env {
+    pub fn new(config: EnvConfig) -> Self {
+        let mut world = headless::new_world(0, &config.game);
+        world.set_mode(config.mode);
+        Env {
+            world,
+            config,
+            steps: 0,
+        }
+    }
+
+    // Starts an episode on the pipe layout for `seed`.
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.world.set_seed(seed);
+        self.start()
+    }
+
+    // Starts an episode on the next seed after the last episode's, so a
+    // series of episodes can be reproduced from the first seed alone.
+    pub fn restart(&mut self) -> Observation {
+        self.start()
+    }
+
+    fn start(&mut self) -> Observation {
+        self.world.start();
+        self.steps = 0;
+        // Runs start with a flap, so the episode does too.
+        self.world.update(&GameInput::flap(), Env::dt());
+        self.observe()
+    }
+
+    // Takes `action` and runs the world on for a step, returning what the
+    // agent sees afterwards, what the step was worth and whether the episode
+    // is over. Once it is, the next step needs a reset or restart first.
+    pub fn step(&mut self, action: Action) -> (Observation, f32, bool) {
+        let rewards = self.config.rewards;
+        let score = self.world.score();
+
+        for i in 0..self.config.frame_skip.max(1) {
+            let input = match action {
+                Action::Flap if i == 0 => GameInput::flap(),
+                _ => GameInput::new(),
+            };
+            self.world.update(&input, Env::dt());
+            if self.is_over() {
+                break;
+            }
+        }
+        self.steps += 1;
+
+        let mut reward = (self.world.score() - score) as f32 * rewards.score;
+        if self.world.bird().is_alive() {
+            reward += rewards.alive;
+        } else {
+            reward += rewards.death;
+        }
+        let timed_out = self.config.max_steps.map_or(false, |max_steps| self.steps >= max_steps);
+
+        (self.observe(), reward, self.is_over() || timed_out)
+    }
+
+    fn is_over(&self) -> bool {
+        !self.world.bird().is_alive() || self.world.is_game_over()
+    }
+
+    fn dt() -> f32 {
+        1.0 / TICK_RATE as f32
+    }
+
+    // What the agent sees of the world as it stands.
+    pub fn observe(&self) -> Observation {
+        let world = &self.world;
+        self.config.observation.observe(world.bird(), world.scroller(), world.config(), world.game_size(),
+                                        world.ground_y())
+    }
+
+    pub fn world(&self) -> &GameWorld {
+        &self.world
+    }
+}
+
+enum Command {
+    Reset(u64),
+    Step(Vec<Action>),
+}
+
+// A thread looking after some of a VecEnv's environments.
+struct Worker {
+    commands: Sender<Command>,
+    results: Receiver<Vec<(Observation, f32, bool)>>,
+    envs: usize,
+    thread: JoinHandle<()>,
+}
+
+// Many environments stepped together, split across threads. Environments
+// whose episode ends start the next one straight away, on the next seed
+// after theirs.
+pub struct VecEnv {
+    workers: Vec<Worker>,
+    count: usize,
+}
+
+impl VecEnv {
+    // Makes `count` environments shared out between `threads` threads.
+    pub fn new(config: EnvConfig, count: usize, threads: usize) -> Self {
+        let threads = threads.max(1).min(count.max(1));
+        let mut first = 0;
+        let workers = (0..threads)
+            .map(|i| {
+                let envs = count / threads + if i < count % threads { 1 } else { 0 };
+                let (commands, command_receiver) = mpsc::channel();
+                let (result_sender, results) = mpsc::channel();
+                let config = config.clone();
+                let offset = first;
+                let thread = thread::spawn(move || {
+                    run_worker(config, envs, offset, command_receiver, result_sender)
+                });
+                first += envs;
+
+                Worker {
+                    commands,
+                    results,
+                    envs,
+                    thread,
+                }
+            })
+            .collect();
+
+        VecEnv {
+            workers,
+            count,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    // Starts every environment on a new episode, the first on `seed`, the
+    // second on `seed + 1` and so on.
+    pub fn reset(&mut self, seed: u64) -> Vec<Observation> {
+        for worker in &self.workers {
+            worker.commands.send(Command::Reset(seed)).expect("environment thread died");
+        }
+        self.collect().into_iter().map(|(observation, _, _)| observation).collect()
+    }
+
+    // Steps every environment with its action, in order. Where an episode
+    // ends, the observation is the first of the next episode.
+    pub fn step(&mut self, actions: &[Action]) -> Vec<(Observation, f32, bool)> {
+        assert_eq!(actions.len(), self.count, "need an action for each environment");
+        let mut actions = actions;
+        for worker in &self.workers {
+            let (mine, rest) = actions.split_at(worker.envs);
+            worker.commands.send(Command::Step(mine.to_vec())).expect("environment thread died");
+            actions = rest;
+        }
+        self.collect()
+    }
+
+    fn collect(&self) -> Vec<(Observation, f32, bool)> {
+        self.workers.iter()
+            .flat_map(|worker| worker.results.recv().expect("environment thread died"))
+            .collect()
+    }
+}
+
+impl Drop for VecEnv {
+    fn drop(&mut self) {
+        for worker in self.workers.drain(..) {
+            // Hanging up tells the thread to finish.
+            drop(worker.commands);
+            let _ = worker.thread.join();
+        }
+    }
+}
+
+// Runs environments `first` onwards until the VecEnv hangs up.
+fn run_worker(config: EnvConfig, count: usize, first: usize, commands: Receiver<Command>,
+              results: Sender<Vec<(Observation, f32, bool)>>) {
+    let mut envs: Vec<Env> = (0..count).map(|_| Env::new(config.clone())).collect();
+
+    for command in commands {
+        let transitions = match command {
+            Command::Reset(seed) => envs.iter_mut()
+                .enumerate()
+                .map(|(i, env)| (env.reset(seed.wrapping_add((first + i) as u64)), 0.0, false))
+                .collect(),
+            Command::Step(actions) => envs.iter_mut()
+                .zip(actions)
+                .map(|(env, action)| {
+                    let (observation, reward, done) = env.step(action);
+                    if done {
+                        (env.restart(), reward, true)
+                    } else {
+                        (observation, reward, false)
+                    }
+                })
+                .collect(),
+        };
+        if results.send(transitions).is_err() {
+            return;
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use std::path::Path;
+
+use rand::Rng;
+
+use bird::Bird;
+use config::GameConfig;
+use difficulty::Difficulty;
+use env::ObservationConfig;
+use genome::Genome;
+use rng::{self, GameRng};
+use scroll_handler::ScrollHandler;
+use world;
+
+
+// Birds in each generation, unless asked for otherwise.
+pub const DEFAULT_POPULATION: usize = 50;
+// Hidden units in each bird's network.
+const HIDDEN_UNITS: usize = 6;
+// Share of each generation carried over unchanged to the next.
+const ELITE_SHARE: f32 = 0.1;
+// How many birds are picked from at random to find each parent; the fittest
+// of them wins.
+const TOURNAMENT_SIZE: usize = 3;
+const MUTATION_RATE: f32 = 0.1;
+const MUTATION_STRENGTH: f64 = 0.4;
+// Fitness a passed pipe is worth, on top of the distance flown.
+const SCORE_FITNESS: f32 = 100.0;
+// Longest a generation flies before the next takes over, in seconds.
+const GENERATION_SECONDS: f32 = 120.0;
+
+// Loads a genome to start an evolution run from, checking it fits the
+// birds' observations.
+pub fn load_ancestor<P: AsRef<Path>>(path: P) -> Result<Genome, String> {
+    let path = path.as_ref();
+    let genome = Genome::load(path)?;
+    let inputs = ObservationConfig::default().size();
+    if genome.inputs() != inputs {
+        return Err(format!("Genome {} takes {} inputs, expected {}", path.display(), genome.inputs(), inputs));
+    }
+    Ok(genome)
+}
+
+// One bird of a generation and how it's doing.
+pub struct Member {
+    bird: Bird,
+    genome: Genome,
+    score: u32,
+    // Set once the bird is out.
+    fitness: f32,
+}
+
+impl Member {
+    pub fn bird(&self) -> &Bird {
+        &self.bird
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+}
+
+// A population of birds, each flown by its own network, learning to fly
+// through one shared row of pipes. Once every bird is down the fittest breed
+// the next generation, which flies a fresh layout.
+pub struct Evolution {
+    // Pickups are left out, since birds flying together would have to share
+    // them.
+    config: GameConfig,
+    observation: ObservationConfig,
+    game_size: (f32, f32),
+    mid_point_y: u32,
+    population: usize,
+
+    // Picks parents, mutations and each generation's seed.
+    rng: GameRng,
+    // Lays out the current generation's pipes.
+    layout_rng: GameRng,
+
+    generation: u32,
+    best_fitness: f32,
+    best_genome: Option<Genome>,
+
+    members: Vec<Member>,
+    scroller: ScrollHandler,
+    // How far the current generation has flown, and for how long.
+    distance: f32,
+    flight_time: f32,
+    score: u32,
+}
+
+impl Evolution {
+    // Starts from random networks, or from mutated copies of `ancestor` and
+    // the ancestor itself if one is given.
+    pub fn new(game_width: f32, game_height: f32, seed: u64, config: &GameConfig, population: usize,
+               ancestor: Option<Genome>) -> Self {
+        let mut config = config.clone();
+        config.pickups.coin_chance = 0.0;
+        config.pickups.shield_chance = 0.0;
+        config.pickups.slow_motion_chance = 0.0;
+
+        let mid_point_y = (game_height / 2.0) as u32;
+        let population = population.max(1);
+        let observation = ObservationConfig::default();
+        let mut rng = rng::seeded_rng(seed);
+        let mut layout_rng = rng::seeded_rng(seed);
+        let difficulty = Difficulty::at(&config, 0, 0.0);
+        let scroller = ScrollHandler::new(mid_point_y as f32 - 66.0, game_width, game_height,
+                                          &config, &difficulty, &mut layout_rng);
+
+        let genomes = (0..population)
+            .map(|i| match ancestor {
+                Some(ref ancestor) if i == 0 => ancestor.clone(),
+                Some(ref ancestor) => {
+                    let mut genome = ancestor.clone();
+                    genome.mutate(MUTATION_RATE, MUTATION_STRENGTH, &mut rng);
+                    genome
+                },
+                None => Genome::random(observation.size(), HIDDEN_UNITS, &mut rng),
+            })
+            .collect();
+
+        let mut evolution = Evolution {
+            config,
+            observation,
+            game_size: (game_width, game_height),
+            mid_point_y,
+            population,
+
+            rng,
+            layout_rng,
+
+            generation: 0,
+            best_fitness: 0.0,
+            best_genome: None,
+
+            members: Vec::new(),
+            scroller,
+            distance: 0.0,
+            flight_time: 0.0,
+            score: 0,
+        };
+        evolution.start_generation(genomes);
+        evolution
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for member in &mut self.members {
+            member.bird.store_previous_state();
+        }
+        self.scroller.store_previous_state();
+
+        let difficulty = Difficulty::at(&self.config, self.score, self.flight_time);
+        let ground_y = self.mid_point_y as f32 - 66.0;
+        for member in self.members.iter_mut().filter(|member| member.bird.is_alive()) {
+            let inputs = self.observation.observe(&member.bird, &self.scroller, &self.config, self.game_size,
+                                                  ground_y);
+            let flap = member.genome.should_flap(&inputs);
+            member.bird.update_running(flap, dt);
+        }
+
+        // Every bird flies at the same x, so any one can stand in for the
+        // rest when it comes to scrolling and scoring.
+        let leader = match self.members.iter().position(|member| member.bird.is_alive()) {
+            Some(leader) => leader,
+            None => {
+                self.next_generation();
+                return;
+            },
+        };
+        self.scroller.update_running(dt, &self.members[leader].bird, &difficulty, &mut self.layout_rng);
+        self.flight_time += dt;
+        self.distance += difficulty.speed * dt;
+
+        if self.scroller.scored(&self.members[leader].bird) {
+            self.score += 1;
+            for member in self.members.iter_mut().filter(|member| member.bird.is_alive()) {
+                member.score += 1;
+            }
+        }
+
+        for member in self.members.iter_mut().filter(|member| member.bird.is_alive()) {
+            if self.scroller.collides(&member.bird) || world::on_ground(&member.bird, self.mid_point_y) {
+                member.bird.die();
+                member.fitness = fitness(self.distance, member.score);
+            }
+        }
+
+        let all_down = self.members.iter().all(|member| !member.bird.is_alive());
+        if all_down || self.flight_time >= GENERATION_SECONDS {
+            for member in self.members.iter_mut().filter(|member| member.bird.is_alive()) {
+                member.fitness = fitness(self.distance, member.score);
+            }
+            self.next_generation();
+        }
+    }
+
+    // Breeds the next generation from the fittest of this one and sends it
+    // off.
+    fn next_generation(&mut self) {
+        self.members.sort_by(|a, b| b.fitness.partial_cmp(&a.fitness).unwrap());
+        let fittest = &self.members[0];
+        if self.best_genome.is_none() || fittest.fitness > self.best_fitness {
+            self.best_fitness = fittest.fitness;
+            self.best_genome = Some(fittest.genome.clone());
+        }
+
+        let elite = ((self.population as f32 * ELITE_SHARE) as usize).max(1);
+        let mut genomes: Vec<Genome> = self.members.iter()
+            .take(elite)
+            .map(|member| member.genome.clone())
+            .collect();
+        while genomes.len() < self.population {
+            let mother = self.pick_parent();
+            let father = self.pick_parent();
+            let mut child = self.members[mother].genome.crossover(&self.members[father].genome, &mut self.rng);
+            child.mutate(MUTATION_RATE, MUTATION_STRENGTH, &mut self.rng);
+            genomes.push(child);
+        }
+
+        self.generation += 1;
+        self.start_generation(genomes);
+    }
+
+    // Picks a parent by tournament, given members sorted fittest first.
+    fn pick_parent(&mut self) -> usize {
+        let count = self.members.len();
+        let rng = &mut self.rng;
+        (0..TOURNAMENT_SIZE).map(|_| rng.gen_range(0, count)).min().unwrap()
+    }
+
+    fn start_generation(&mut self, genomes: Vec<Genome>) {
+        let seed = self.rng.gen();
+        self.layout_rng = rng::seeded_rng(seed);
+        let difficulty = Difficulty::at(&self.config, 0, 0.0);
+        self.scroller.on_restart(&difficulty, &mut self.layout_rng);
+        self.distance = 0.0;
+        self.flight_time = 0.0;
+        self.score = 0;
+
+        let start_y = self.mid_point_y as f32 + 5.0;
+        let (_, game_height) = self.game_size;
+        let bird_config = self.config.bird;
+        self.members = genomes.into_iter()
+            .map(|genome| Member {
+                bird: Bird::new(33.0, start_y, 17, 12, game_height, bird_config),
+                genome,
+                score: 0,
+                fitness: 0.0,
+            })
+            .collect();
+    }
+
+    // Generations finished so far.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    // Fitness of the best bird of any finished generation.
+    pub fn best_fitness(&self) -> f32 {
+        self.best_fitness
+    }
+
+    pub fn best_genome(&self) -> Option<&Genome> {
+        self.best_genome.as_ref()
+    }
+
+    pub fn members(&self) -> &[Member] {
+        &self.members
+    }
+
+    pub fn alive(&self) -> usize {
+        self.members.iter().filter(|member| member.bird.is_alive()).count()
+    }
+
+    pub fn scroller(&self) -> &ScrollHandler {
+        &self.scroller
+    }
+
+    // Pipes the current generation has passed.
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    // Seconds the current generation has been flying.
+    pub fn flight_time(&self) -> f32 {
+        self.flight_time
+    }
+
+    pub fn mid_point_y(&self) -> u32 {
+        self.mid_point_y
+    }
+}
+
+fn fitness(distance: f32, score: u32) -> f32 {
+    distance + score as f32 * SCORE_FITNESS
+}
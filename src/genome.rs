@@ -0,0 +1,169 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use rand::Rng;
+use rand::distributions::{IndependentSample, Normal};
+
+use paths;
+use replay::parse_one;
+use rng::GameRng;
+
+
+const MAGIC: &str = "zombie-bird-genome 1";
+
+// The brain of a bird that learns to fly: a small neural network from an
+// observation (see env::ObservationConfig) through one hidden layer of tanh
+// units to a single output, which flaps when it's positive.
+//
+// On disk this is a short text file:
+//
+//     zombie-bird-genome 1
+//     inputs 8
+//     hidden 6
+//     weights 0.52 -1.3 ...
+//
+// where weights lists each hidden unit's input weights and then its bias,
+// followed by the output's weight for each hidden unit and then its bias.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Genome {
+    inputs: usize,
+    hidden: usize,
+    weights: Vec<f32>,
+}
+
+impl Genome {
+    // A network with weights picked at random between -1 and 1.
+    pub fn random(inputs: usize, hidden: usize, rng: &mut GameRng) -> Self {
+        let weights = (0..Genome::weight_count(inputs, hidden))
+            .map(|_| rng.gen::<f32>() * 2.0 - 1.0)
+            .collect();
+        Genome {
+            inputs,
+            hidden,
+            weights,
+        }
+    }
+
+    fn weight_count(inputs: usize, hidden: usize) -> usize {
+        (inputs + 1) * hidden + hidden + 1
+    }
+
+    pub fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    pub fn hidden(&self) -> usize {
+        self.hidden
+    }
+
+    // Whether the network flaps, given an observation.
+    pub fn should_flap(&self, inputs: &[f32]) -> bool {
+        let (hidden_weights, output_weights) = self.weights.split_at((self.inputs + 1) * self.hidden);
+        let mut output = output_weights[self.hidden];
+        for (unit, weights) in hidden_weights.chunks(self.inputs + 1).enumerate() {
+            let sum: f32 = weights.iter().zip(inputs).map(|(weight, input)| weight * input).sum();
+            output += output_weights[unit] * (sum + weights[self.inputs]).tanh();
+        }
+        output > 0.0
+    }
+
+    // A child taking each weight from one parent or the other at random.
+    // Both parents need the same shape.
+    pub fn crossover(&self, other: &Genome, rng: &mut GameRng) -> Genome {
+        let weights = self.weights.iter()
+            .zip(&other.weights)
+            .map(|(&mine, &theirs)| if rng.gen() { mine } else { theirs })
+            .collect();
+        Genome {
+            weights,
+            .. self.clone()
+        }
+    }
+
+    // Nudges each weight, with chance `rate`, by a normally distributed
+    // amount with standard deviation `strength`.
+    pub fn mutate(&mut self, rate: f32, strength: f64, rng: &mut GameRng) {
+        let nudge = Normal::new(0.0, strength);
+        for weight in &mut self.weights {
+            if rng.gen::<f32>() < rate {
+                *weight += nudge.ind_sample(rng) as f32;
+            }
+        }
+    }
+
+    // Where the best genome of an evolution run is saved.
+    pub fn default_path() -> PathBuf {
+        paths::data_dir().join("best.zbn")
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let mut text = String::new();
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut text))
+            .map_err(|e| format!("Could not read genome {}: {}", path.display(), e))?;
+        Genome::parse(&text)
+            .map_err(|e| format!("Invalid genome {}: {}", path.display(), e))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        paths::write_atomically(path.as_ref(), self.to_string().as_bytes())
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines();
+        if lines.next().map(str::trim) != Some(MAGIC) {
+            return Err("not a genome file".to_string());
+        }
+
+        let mut inputs = None;
+        let mut hidden = None;
+        let mut weights = Vec::new();
+        for line in lines {
+            let mut words = line.split_whitespace();
+            let key = match words.next() {
+                Some(key) => key,
+                None => continue,
+            };
+            let values: Vec<&str> = words.collect();
+            match key {
+                "inputs" => inputs = Some(parse_one(key, &values)?),
+                "hidden" => hidden = Some(parse_one(key, &values)?),
+                "weights" => {
+                    weights = values.iter()
+                        .map(|value| value.parse().map_err(|_| format!("invalid weight \"{}\"", value)))
+                        .collect::<Result<Vec<f32>, String>>()?;
+                },
+                _ => return Err(format!("unknown field \"{}\"", key)),
+            }
+        }
+
+        let inputs = inputs.ok_or("missing inputs")?;
+        let hidden = hidden.ok_or("missing hidden")?;
+        let expected = Genome::weight_count(inputs, hidden);
+        if weights.len() != expected {
+            return Err(format!("{} weights, expected {}", weights.len(), expected));
+        }
+
+        Ok(Genome {
+            inputs,
+            hidden,
+            weights,
+        })
+    }
+}
+
+impl fmt::Display for Genome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", MAGIC)?;
+        writeln!(f, "inputs {}", self.inputs)?;
+        writeln!(f, "hidden {}", self.hidden)?;
+        write!(f, "weights")?;
+        for weight in &self.weights {
+            write!(f, " {}", weight)?;
+        }
+        writeln!(f)
+    }
+}
@@ -0,0 +1,137 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use mode::GameMode;
+use paths;
+use replay::parse_one;
+
+
+const MAGIC: &str = "zombie-bird-ghost 1";
+
+// Where the bird was on one tick of a run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GhostFrame {
+    pub y: f32,
+    pub rotation: f32,
+}
+
+impl GhostFrame {
+    // The frame `alpha` of the way from this one to `next`.
+    pub fn lerp(&self, next: GhostFrame, alpha: f32) -> GhostFrame {
+        GhostFrame {
+            y: self.y + (next.y - self.y) * alpha,
+            rotation: self.rotation + (next.rotation - self.rotation) * alpha,
+        }
+    }
+}
+
+// The flight of the best run on a seed, one frame per tick from the first
+// flap on, to race against on later runs with the same seed.
+//
+// On disk this is a short text file:
+//
+//     zombie-bird-ghost 1
+//     seed 1234
+//     mode classic
+//     score 12
+//     frames 107 0 107.9 20 ...
+//
+// where frames lists each tick's height and rotation in turn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ghost {
+    pub seed: u64,
+    pub mode: GameMode,
+    pub score: u32,
+    pub frames: Vec<GhostFrame>,
+}
+
+impl Ghost {
+    // Where the best run on a seed is kept. Modes lay pipes out differently,
+    // so each keeps its own.
+    pub fn path(seed: u64, mode: GameMode) -> PathBuf {
+        paths::ghost_dir().join(format!("{}-{}.zbg", mode, seed))
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let mut text = String::new();
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut text))
+            .map_err(|e| format!("Could not read ghost {}: {}", path.display(), e))?;
+        Ghost::parse(&text)
+            .map_err(|e| format!("Invalid ghost {}: {}", path.display(), e))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        paths::write_atomically(path.as_ref(), self.to_string().as_bytes())
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines();
+        if lines.next().map(str::trim) != Some(MAGIC) {
+            return Err("not a ghost file".to_string());
+        }
+
+        let mut seed = None;
+        let mut mode = None;
+        let mut score = 0;
+        let mut frames = Vec::new();
+        for line in lines {
+            let mut words = line.split_whitespace();
+            let key = match words.next() {
+                Some(key) => key,
+                None => continue,
+            };
+            let values: Vec<&str> = words.collect();
+            match key {
+                "seed" => seed = Some(parse_one(key, &values)?),
+                "mode" => {
+                    let name: String = parse_one(key, &values)?;
+                    mode = Some(GameMode::from_name(&name).ok_or(format!("unknown mode \"{}\"", name))?);
+                },
+                "score" => score = parse_one(key, &values)?,
+                "frames" => {
+                    let numbers = values.iter()
+                        .map(|value| value.parse().map_err(|_| format!("invalid frame value \"{}\"", value)))
+                        .collect::<Result<Vec<f32>, String>>()?;
+                    if numbers.len() % 2 != 0 {
+                        return Err("frames need a height and a rotation each".to_string());
+                    }
+                    frames = numbers.chunks(2)
+                        .map(|pair| GhostFrame { y: pair[0], rotation: pair[1] })
+                        .collect();
+                },
+                _ => return Err(format!("unknown field \"{}\"", key)),
+            }
+        }
+
+        Ok(Ghost {
+            seed: seed.ok_or("missing seed")?,
+            mode: mode.ok_or("missing mode")?,
+            score,
+            frames,
+        })
+    }
+
+    // The frame for `tick` ticks after the first flap. Past the end of the
+    // run the ghost stays where it ended up.
+    pub fn frame(&self, tick: u32) -> Option<GhostFrame> {
+        self.frames.get(tick as usize).or_else(|| self.frames.last()).cloned()
+    }
+}
+
+impl fmt::Display for Ghost {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", MAGIC)?;
+        writeln!(f, "seed {}", self.seed)?;
+        writeln!(f, "mode {}", self.mode)?;
+        writeln!(f, "score {}", self.score)?;
+        write!(f, "frames")?;
+        for frame in &self.frames {
+            write!(f, " {} {}", frame.y, frame.rotation)?;
+        }
+        writeln!(f)
+    }
+}
@@ -0,0 +1,122 @@
+use autopilot;
+use bird::Bird;
+use config::{GameConfig, TICK_RATE};
+use mode::GameMode;
+use replay::{Replay, ReplayPlayer};
+use world::GameWorld;
+
+
+// How long past its recorded end a replay is simulated before giving up on
+// seeing the run finish.
+const EXTRA_SECONDS: u32 = 30;
+// Longest a soak test run is flown before moving on to the next, in seconds.
+const SOAK_RUN_SECONDS: u32 = 300;
+
+// A world laid out for the config's screen, as the game would make it.
+pub fn new_world(seed: u64, config: &GameConfig) -> GameWorld {
+    let (game_width, game_height) = config.screen.game_size();
+    GameWorld::new(game_width, game_height, seed, config.clone())
+}
+
+// Plays a replay back without a window, returning the run as recorded during
+// playback.
+pub fn play_replay(replay: &Replay, config: &GameConfig) -> Replay {
+    let (game_width, game_height) = replay.game_size;
+    let mut world = GameWorld::new(game_width, game_height, replay.seed, config.clone());
+    world.set_tick_rate(replay.tick_rate);
+    world.set_reuse_seed(true);
+    world.set_mode(replay.mode);
+    world.set_players(replay.flaps.len());
+
+    let dt = 1.0 / replay.tick_rate as f32;
+    let last_flap = replay.flaps.iter().filter_map(|flaps| flaps.last().cloned()).max();
+    let last_tick = replay.death_tick
+        .or(last_flap)
+        .unwrap_or(0) + EXTRA_SECONDS * replay.tick_rate;
+
+    let mut player = ReplayPlayer::new(replay.clone());
+    while world.run_tick() <= last_tick && !world.is_game_over() {
+        let input = player.input(world.run_tick());
+        world.update(&input, dt);
+    }
+
+    world.replay().clone()
+}
+
+// Plays a replay back and checks it ends the way it was recorded. Only
+// meaningful with the config the run was recorded under.
+pub fn verify_replay(replay: &Replay, config: &GameConfig) -> Result<Replay, String> {
+    let played = play_replay(replay, config);
+    replay.check(&played)?;
+    Ok(played)
+}
+
+// How a soak test went.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SoakStats {
+    pub runs: u32,
+    // Runs that ended before the time cap, and had their replay checked.
+    pub ended: u32,
+    pub ticks: u64,
+    pub best_score: u32,
+    pub total_score: u64,
+}
+
+// Flies `runs` autopilot runs back to back from `seed`, checking that every
+// bird stays on screen with sane numbers, and that every run that ends plays
+// back the same from its replay. Stops at the first run that goes wrong.
+pub fn soak(runs: u32, seed: u64, mode: GameMode, players: usize, config: &GameConfig) -> Result<SoakStats, String> {
+    let mut world = new_world(seed, config);
+    world.set_mode(mode);
+    world.set_players(players);
+
+    let dt = 1.0 / TICK_RATE as f32;
+    let mut stats = SoakStats::default();
+    for run in 0..runs {
+        world.start();
+        let fail = |world: &GameWorld, e: String| {
+            format!("run {} on seed {} failed on tick {}: {}", run + 1, world.seed(), world.run_tick(), e)
+        };
+
+        while world.run_tick() < SOAK_RUN_SECONDS * TICK_RATE && !world.is_game_over() {
+            let input = autopilot::input(&world);
+            world.update(&input, dt);
+            for (i, player) in world.players().iter().enumerate() {
+                check_bird(player.bird(), &world)
+                    .map_err(|e| fail(&world, format!("player {}: {}", i + 1, e)))?;
+            }
+        }
+
+        stats.runs += 1;
+        stats.ticks += world.run_tick() as u64;
+        stats.best_score = stats.best_score.max(world.score());
+        stats.total_score += world.score() as u64;
+        if world.is_game_over() {
+            stats.ended += 1;
+            verify_replay(world.replay(), config)
+                .map_err(|e| fail(&world, format!("replay diverged: {}", e)))?;
+        }
+    }
+
+    Ok(stats)
+}
+
+// Checks the bird is somewhere it could be: between the ground and a little
+// above the top of the screen, with finite speed.
+fn check_bird(bird: &Bird, world: &GameWorld) -> Result<(), String> {
+    let position = bird.position();
+    let velocity = bird.velocity();
+    if !(position.x.is_finite() && position.y.is_finite() && velocity.x.is_finite() && velocity.y.is_finite()) {
+        return Err(format!("bird at {:?} moving at {:?}", position, velocity));
+    }
+
+    // A tick's fall can take the bird into the ground before it's caught, and
+    // the ceiling only pushes it back down on the next tick.
+    let slack = bird.height() as f32;
+    let ground = world.ground_y();
+    let ceiling = world.game_size().1 + world.config().bird.radius;
+    if position.y < ground - slack || position.y > ceiling + slack {
+        return Err(format!("bird left the screen at {:?}", position));
+    }
+    Ok(())
+}
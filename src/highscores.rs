@@ -0,0 +1,194 @@
+use std::cmp::Reverse;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use toml;
+
+use date::Date;
+use mode::GameMode;
+use paths;
+
+
+pub const MAX_HIGH_SCORES: usize = 10;
+// Characters a name can be shown with. The game's font only has lowercase
+// letters, digits and a little punctuation.
+pub const NAME_CHARACTERS: &str = "abcdefghijklmnopqrstuvwxyz0123456789 !.-";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HighScore {
+    pub score: u32,
+    pub date: Date,
+    pub seed: u64,
+    pub name: Option<String>,
+}
+
+// The best scores so far, best first, backed by a TOML file:
+//
+//     [[scores]]
+//     score = 12
+//     date = "2016-11-02"
+//     seed = "8215442919021538716"
+//     name = "gabe"
+pub struct HighScoreTable {
+    path: PathBuf,
+    scores: Vec<HighScore>,
+    // Position of the score added most recently, if any.
+    latest: Option<usize>,
+}
+
+impl HighScoreTable {
+    // Each mode keeps its own table. Classic keeps the original file name so
+    // scores from before there were modes carry over.
+    pub fn default_path(mode: GameMode) -> PathBuf {
+        let file_name = match mode {
+            GameMode::Classic => "highscores.toml".to_string(),
+            mode => format!("highscores-{}.toml", mode),
+        };
+        paths::data_dir().join(file_name)
+    }
+
+    // Loads the table at `path`. A missing file gives an empty table, and a
+    // corrupt one is moved aside so the next save doesn't clobber it.
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let mut table = HighScoreTable {
+            path,
+            scores: Vec::new(),
+            latest: None,
+        };
+
+        let mut text = String::new();
+        match File::open(&table.path).and_then(|mut file| file.read_to_string(&mut text)) {
+            Ok(_) => {},
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return table,
+            Err(e) => {
+                println!("Could not read high scores from {}: {}", table.path.display(), e);
+                return table;
+            },
+        }
+
+        match parse_scores(&text) {
+            Ok(scores) => table.scores = scores,
+            Err(e) => {
+                let backup = table.path.with_extension("toml.corrupt");
+                println!("High scores in {} are corrupt ({}), moving them to {}",
+                         table.path.display(), e, backup.display());
+                if let Err(e) = fs::rename(&table.path, &backup) {
+                    println!("Could not move corrupt high scores: {}", e);
+                }
+            },
+        }
+
+        table
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        paths::write_atomically(&self.path, self.to_toml().to_string().as_bytes())
+    }
+
+    pub fn best(&self) -> u32 {
+        self.scores.first().map_or(0, |entry| entry.score)
+    }
+
+    // Whether a run scoring `score` would make it into the table.
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0 && (self.scores.len() < MAX_HIGH_SCORES ||
+                      score > self.scores[MAX_HIGH_SCORES - 1].score)
+    }
+
+    // Adds a score, returning its position in the table if it made the cut.
+    pub fn insert(&mut self, entry: HighScore) -> Option<usize> {
+        if !self.qualifies(entry.score) {
+            return None;
+        }
+
+        // Ties go below existing scores; they got there first.
+        let position = self.scores.iter()
+            .position(|other| entry.score > other.score)
+            .unwrap_or(self.scores.len());
+        self.scores.insert(position, entry);
+        self.scores.truncate(MAX_HIGH_SCORES);
+        self.latest = Some(position);
+
+        self.latest
+    }
+
+    pub fn scores(&self) -> &[HighScore] {
+        &self.scores
+    }
+
+    pub fn latest(&self) -> Option<usize> {
+        self.latest
+    }
+
+    fn to_toml(&self) -> toml::Value {
+        let scores = self.scores.iter().map(|entry| {
+            let mut table = toml::value::Table::new();
+            table.insert("score".to_string(), toml::Value::Integer(entry.score as i64));
+            table.insert("date".to_string(), toml::Value::String(entry.date.to_string()));
+            // Seeds use all 64 bits, which TOML integers can't hold.
+            table.insert("seed".to_string(), toml::Value::String(entry.seed.to_string()));
+            if let Some(ref name) = entry.name {
+                table.insert("name".to_string(), toml::Value::String(name.clone()));
+            }
+            toml::Value::Table(table)
+        }).collect();
+
+        let mut root = toml::value::Table::new();
+        root.insert("scores".to_string(), toml::Value::Array(scores));
+        toml::Value::Table(root)
+    }
+}
+
+fn parse_scores(text: &str) -> Result<Vec<HighScore>, String> {
+    let root: toml::Value = text.parse().map_err(|e: toml::de::Error| e.to_string())?;
+    let entries = match root.get("scores") {
+        Some(toml::Value::Array(entries)) => entries,
+        Some(_) => return Err("\"scores\" is not an array".to_string()),
+        None => return Ok(Vec::new()),
+    };
+
+    let mut scores = entries.iter()
+        .map(parse_entry)
+        .collect::<Result<Vec<_>, _>>()?;
+    // Don't trust the file to be sorted.
+    scores.sort_by_key(|entry| Reverse(entry.score));
+    scores.truncate(MAX_HIGH_SCORES);
+
+    Ok(scores)
+}
+
+fn parse_entry(value: &toml::Value) -> Result<HighScore, String> {
+    let score = value.get("score")
+        .and_then(toml::Value::as_integer)
+        .filter(|&score| score >= 0)
+        .ok_or("score entry without a valid score")?;
+    let date = value.get("date")
+        .and_then(toml::Value::as_str)
+        .ok_or("score entry without a date")?
+        .parse()?;
+    let seed = value.get("seed")
+        .and_then(toml::Value::as_str)
+        .and_then(|seed| seed.parse().ok())
+        .ok_or("score entry without a valid seed")?;
+    let name = value.get("name")
+        .and_then(toml::Value::as_str)
+        .map(drawable_name);
+
+    Ok(HighScore {
+        score: score as u32,
+        date,
+        seed,
+        name,
+    })
+}
+
+// Lowercases the name and swaps any character the font can't draw for "?",
+// e.g. for names typed into the high score file by hand.
+pub fn drawable_name(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(|c| if NAME_CHARACTERS.contains(c) { c } else { '?' })
+        .collect()
+}
@@ -0,0 +1,62 @@
+/// Most birds that can fly in one run.
+pub const MAX_PLAYERS: usize = 4;
+
+/// Input for a single simulation tick.
+///
+/// The world never polls a window or keyboard itself; whoever drives it (the
+/// Midgar app, a replay, a test) fills one of these in for every update.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GameInput {
+    // Whether each player flapped, in player order.
+    pub flaps: [bool; MAX_PLAYERS],
+    pub restart: bool,
+    // Pauses a run, or starts the countdown to resume a paused one.
+    pub pause: bool,
+}
+
+impl GameInput {
+    pub fn new() -> Self {
+        GameInput::default()
+    }
+
+    // The first player flapping.
+    pub fn flap() -> Self {
+        GameInput::flap_player(0)
+    }
+
+    pub fn flap_player(player: usize) -> Self {
+        let mut input = GameInput::default();
+        input.flaps[player] = true;
+        input
+    }
+
+    pub fn restart() -> Self {
+        GameInput {
+            restart: true,
+            .. GameInput::default()
+        }
+    }
+
+    // Combine presses from several frames into one tick's worth of input.
+    pub fn merge(&mut self, other: &GameInput) {
+        for (flap, other_flap) in self.flaps.iter_mut().zip(&other.flaps) {
+            *flap |= *other_flap;
+        }
+        self.restart |= other.restart;
+        self.pause |= other.pause;
+    }
+}
+
+/// Input for navigating menus, gathered once per frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MenuInput {
+    pub up: bool,
+    pub down: bool,
+    pub confirm: bool,
+    pub back: bool,
+    // Whether the mouse button (or a finger) went down or came up this
+    // frame, and which entry of the menu on screen it was over, if any.
+    pub press: bool,
+    pub release: bool,
+    pub pointer: Option<usize>,
+}
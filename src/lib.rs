@@ -0,0 +1,39 @@
+// The game's simulation, without a window: the world and everything in it,
+// plus replays, scores and the other bits the game keeps on disk. The game
+// itself is the binary, built with the "gui" feature.
+//
+// env wraps a headless GameWorld for training agents on, and evolution trains
+// a flock of neural network birds (see genome) by itself.
+
+extern crate cgmath;
+extern crate nalgebra;
+extern crate ncollide;
+extern crate rand;
+extern crate toml;
+
+pub mod autopilot;
+pub mod bindings;
+pub mod bird;
+pub mod cli;
+pub mod config;
+pub mod daily;
+pub mod date;
+pub mod difficulty;
+pub mod env;
+pub mod evolution;
+pub mod genome;
+pub mod ghost;
+pub mod headless;
+pub mod highscores;
+pub mod input;
+pub mod medal;
+pub mod menu;
+pub mod mode;
+pub mod paths;
+pub mod pickup;
+pub mod replay;
+pub mod rng;
+pub mod scroll_handler;
+pub mod settings;
+pub mod watcher;
+pub mod world;
@@ -1,26 +1,100 @@
 extern crate bmfont;
 extern crate cgmath;
 extern crate ears;
+extern crate image;
 extern crate midgar;
-extern crate nalgebra;
-extern crate ncollide;
-extern crate rand;
+extern crate zombie_bird;
 
-use config::*;
+use std::process;
+
+use zombie_bird::{autopilot, bindings, bird, cli, config, daily, date, evolution, genome, ghost, headless,
+                  highscores, input, medal, menu, mode, paths, pickup, replay, rng, scroll_handler, settings, watcher,
+                  world};
+
+use cli::Options;
+use date::Date;
+use mode::GameMode;
+use replay::Replay;
+use settings::Settings;
 
 mod app;
-mod bird;
-mod config;
+mod assets;
+mod controls;
 mod renderer;
-mod scroll_handler;
 mod sounds;
-mod world;
 
 
 fn main() {
+    let options = match Options::from_env() {
+        Ok(options) => options,
+        Err(e) => {
+            println!("{}\n\n{}", e, cli::USAGE);
+            process::exit(1);
+        },
+    };
+    if options.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+
+    let game_config = match options.load_config() {
+        Ok(game_config) => game_config,
+        Err(e) => {
+            println!("{}", e);
+            process::exit(1);
+        },
+    };
+
+    if let Some(ref path) = options.verify {
+        let result = Replay::load(path).and_then(|replay| headless::verify_replay(&replay, &game_config));
+        match result {
+            Ok(played) => {
+                println!("Replay OK: score {}, died on tick {:?}", played.score, played.death_tick);
+                return;
+            },
+            Err(e) => {
+                println!("Replay FAILED: {}", e);
+                process::exit(1);
+            },
+        }
+    }
+    if let Some(runs) = options.soak {
+        let mode = options.mode.unwrap_or(GameMode::Classic);
+        let seed = match mode {
+            GameMode::Daily => rng::daily_seed(Date::today()),
+            _ => options.seed.unwrap_or_else(rng::random_seed),
+        };
+        println!("Soaking {} runs of {} from seed {}", runs, mode, seed);
+        match headless::soak(runs, seed, mode, options.players.unwrap_or(1), &game_config) {
+            Ok(stats) => {
+                println!("Soak OK: {} runs ({} ended), {} ticks, best score {}, mean score {:.1}",
+                         stats.runs, stats.ended, stats.ticks, stats.best_score,
+                         stats.total_score as f64 / stats.runs as f64);
+                return;
+            },
+            Err(e) => {
+                println!("Soak FAILED: {}", e);
+                process::exit(1);
+            },
+        }
+    }
+    if let Some(ref path) = options.genome {
+        if let Err(e) = evolution::load_ancestor(path) {
+            println!("{}", e);
+            process::exit(1);
+        }
+    }
+    if let Some(ref path) = options.replay {
+        if let Err(e) = Replay::load(path) {
+            println!("{}", e);
+            process::exit(1);
+        }
+    }
+
+    let scale = Settings::load(Settings::default_path()).scale;
     let config = midgar::MidgarAppConfig::new()
         .with_title("Zombie Bird")
-        .with_screen_size((SCREEN_SIZE.0 * DEFAULT_SCALE, SCREEN_SIZE.1 * DEFAULT_SCALE))
+        .with_screen_size(game_config.screen.window_size(scale))
         .with_vsync(false)
         .with_fps(240);
     let app: midgar::MidgarApp<app::GameApp> = midgar::MidgarApp::new(config);
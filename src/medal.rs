@@ -0,0 +1,40 @@
+use config::MedalConfig;
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Medal {
+    Bronze,
+    Silver,
+    Gold,
+    Platinum,
+}
+
+impl Medal {
+    // The best medal a score earns, if any.
+    pub fn for_score(score: u32, config: &MedalConfig) -> Option<Medal> {
+        let medals = [Medal::Bronze, Medal::Silver, Medal::Gold, Medal::Platinum];
+        medals.iter()
+            .zip(config.thresholds.iter())
+            .filter(|&(_, &threshold)| score >= threshold)
+            .map(|(&medal, _)| medal)
+            .next_back()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Medal::Bronze => "bronze",
+            Medal::Silver => "silver",
+            Medal::Gold => "gold",
+            Medal::Platinum => "platinum",
+        }
+    }
+
+    pub fn color(&self) -> [f32; 3] {
+        match *self {
+            Medal::Bronze => [205.0 / 255.0, 127.0 / 255.0, 50.0 / 255.0],
+            Medal::Silver => [192.0 / 255.0, 192.0 / 255.0, 192.0 / 255.0],
+            Medal::Gold => [1.0, 215.0 / 255.0, 0.0],
+            Medal::Platinum => [229.0 / 255.0, 228.0 / 255.0, 226.0 / 255.0],
+        }
+    }
+}
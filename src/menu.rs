@@ -0,0 +1,275 @@
+use bindings::{Action, Bindings};
+use input::MenuInput;
+use mode::GameMode;
+use settings::Settings;
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MenuAction {
+    Play,
+    ToggleMode,
+    TogglePlayers,
+    HighScores,
+    Settings,
+    Quit,
+    ToggleScale,
+    ToggleSound,
+    Controls,
+    // Waits for the next control pressed to bind it to the action.
+    Rebind(Action),
+    ResetControls,
+    Retry,
+    Resume,
+    MainMenu,
+    Back,
+}
+
+pub struct MenuEntry {
+    pub action: MenuAction,
+    pub label: String,
+}
+
+impl MenuEntry {
+    pub fn new(action: MenuAction, label: &str) -> Self {
+        MenuEntry {
+            action,
+            label: label.to_string(),
+        }
+    }
+}
+
+// A vertical list of entries with one of them selected.
+pub struct Menu {
+    entries: Vec<MenuEntry>,
+    selected: usize,
+    // Entry the pointer went down on, which is picked if it comes up there
+    // too.
+    pressed: Option<usize>,
+}
+
+impl Menu {
+    pub fn new(entries: Vec<MenuEntry>) -> Self {
+        Menu {
+            entries,
+            selected: 0,
+            pressed: None,
+        }
+    }
+
+    // Moves the selection and returns the action picked this frame, if any.
+    pub fn update(&mut self, input: &MenuInput) -> Option<MenuAction> {
+        let count = self.entries.len();
+        if input.up {
+            self.selected = (self.selected + count - 1) % count;
+        }
+        if input.down {
+            self.selected = (self.selected + 1) % count;
+        }
+
+        if input.press {
+            self.pressed = input.pointer.filter(|&i| i < count);
+            if let Some(i) = self.pressed {
+                self.selected = i;
+            }
+        }
+        if input.release {
+            let pressed = self.pressed.take();
+            if pressed.is_some() && pressed == input.pointer {
+                return Some(self.entries[self.selected].action);
+            }
+        }
+
+        if input.confirm {
+            Some(self.entries[self.selected].action)
+        } else if input.back {
+            Some(MenuAction::Back)
+        } else {
+            None
+        }
+    }
+
+    pub fn reset_selection(&mut self) {
+        self.selected = 0;
+        self.pressed = None;
+    }
+
+    pub fn set_label(&mut self, action: MenuAction, label: String) {
+        for entry in self.entries.iter_mut().filter(|entry| entry.action == action) {
+            entry.label = label.clone();
+        }
+    }
+
+    pub fn entries(&self) -> &[MenuEntry] {
+        &self.entries
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    // The entry being held down, if any.
+    pub fn pressed(&self) -> Option<usize> {
+        self.pressed
+    }
+}
+
+// Which page of the title screen is up.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MenuScreen {
+    Title,
+    HighScores,
+    Settings,
+    Controls,
+}
+
+pub struct Menus {
+    screen: MenuScreen,
+    title: Menu,
+    settings: Menu,
+    controls: Menu,
+    game_over: Menu,
+    pause: Menu,
+}
+
+impl Menus {
+    pub fn new(settings: &Settings) -> Self {
+        let title = Menu::new(vec![
+            MenuEntry::new(MenuAction::Play, "play"),
+            MenuEntry::new(MenuAction::ToggleMode, ""),
+            MenuEntry::new(MenuAction::TogglePlayers, ""),
+            MenuEntry::new(MenuAction::HighScores, "high scores"),
+            MenuEntry::new(MenuAction::Settings, "settings"),
+            MenuEntry::new(MenuAction::Quit, "quit"),
+        ]);
+        let settings_menu = Menu::new(vec![
+            MenuEntry::new(MenuAction::ToggleScale, ""),
+            MenuEntry::new(MenuAction::ToggleSound, ""),
+            MenuEntry::new(MenuAction::Controls, "controls"),
+            MenuEntry::new(MenuAction::Back, "back"),
+        ]);
+        let mut controls_entries: Vec<MenuEntry> = Action::ALL.iter()
+            .map(|&action| MenuEntry::new(MenuAction::Rebind(action), ""))
+            .collect();
+        controls_entries.push(MenuEntry::new(MenuAction::ResetControls, "reset"));
+        controls_entries.push(MenuEntry::new(MenuAction::Back, "back"));
+        let controls = Menu::new(controls_entries);
+        let game_over = Menu::new(vec![
+            MenuEntry::new(MenuAction::Retry, "retry"),
+            MenuEntry::new(MenuAction::MainMenu, "menu"),
+        ]);
+        let pause = Menu::new(vec![
+            MenuEntry::new(MenuAction::Resume, "resume"),
+            MenuEntry::new(MenuAction::MainMenu, "menu"),
+        ]);
+
+        let mut menus = Menus {
+            screen: MenuScreen::Title,
+            title,
+            settings: settings_menu,
+            controls,
+            game_over,
+            pause,
+        };
+        menus.refresh_settings(settings);
+        menus.refresh_mode(GameMode::Classic);
+        menus.refresh_players(1);
+        menus
+    }
+
+    pub fn update(&mut self, input: &MenuInput) -> Option<MenuAction> {
+        match self.screen {
+            MenuScreen::Title => self.title.update(input),
+            // Clicking anywhere goes back too.
+            MenuScreen::HighScores => if input.confirm || input.back || input.release {
+                Some(MenuAction::Back)
+            } else {
+                None
+            },
+            MenuScreen::Settings => self.settings.update(input),
+            MenuScreen::Controls => self.controls.update(input),
+        }
+    }
+
+    // Handles the options on the game over screen. Backing out of it goes to
+    // the title screen.
+    pub fn update_game_over(&mut self, input: &MenuInput) -> Option<MenuAction> {
+        match self.game_over.update(input) {
+            Some(MenuAction::Back) => Some(MenuAction::MainMenu),
+            action => action,
+        }
+    }
+
+    pub fn reset_game_over(&mut self) {
+        self.game_over.reset_selection();
+    }
+
+    // Handles the options on the pause screen. Backing out of it resumes.
+    pub fn update_pause(&mut self, input: &MenuInput) -> Option<MenuAction> {
+        match self.pause.update(input) {
+            Some(MenuAction::Back) => Some(MenuAction::Resume),
+            action => action,
+        }
+    }
+
+    pub fn reset_pause(&mut self) {
+        self.pause.reset_selection();
+    }
+
+    // Updates the settings entries to show the current values.
+    pub fn refresh_settings(&mut self, settings: &Settings) {
+        self.settings.set_label(MenuAction::ToggleScale, format!("scale {}x", settings.scale));
+        let sound = if settings.sound { "on" } else { "off" };
+        self.settings.set_label(MenuAction::ToggleSound, format!("sound {}", sound));
+        self.refresh_bindings(&settings.bindings);
+    }
+
+    // Updates the controls entries to show what each action is bound to.
+    pub fn refresh_bindings(&mut self, bindings: &Bindings) {
+        for &action in &Action::ALL {
+            self.controls.set_label(MenuAction::Rebind(action), bindings.label(action));
+        }
+    }
+
+    // Marks the action's entry as waiting for a control to be pressed.
+    pub fn show_rebinding(&mut self, action: Action) {
+        self.controls.set_label(MenuAction::Rebind(action), format!("{} ?", action));
+    }
+
+    // Updates the title screen to show the mode the next run is played in.
+    pub fn refresh_mode(&mut self, mode: GameMode) {
+        self.title.set_label(MenuAction::ToggleMode, format!("mode {}", mode));
+    }
+
+    // Updates the title screen to show how many birds the next run has.
+    pub fn refresh_players(&mut self, players: usize) {
+        self.title.set_label(MenuAction::TogglePlayers, format!("players {}", players));
+    }
+
+    pub fn set_screen(&mut self, screen: MenuScreen) {
+        self.screen = screen;
+    }
+
+    pub fn screen(&self) -> MenuScreen {
+        self.screen
+    }
+
+    pub fn title(&self) -> &Menu {
+        &self.title
+    }
+
+    pub fn settings(&self) -> &Menu {
+        &self.settings
+    }
+
+    pub fn controls(&self) -> &Menu {
+        &self.controls
+    }
+
+    pub fn game_over(&self) -> &Menu {
+        &self.game_over
+    }
+
+    pub fn pause(&self) -> &Menu {
+        &self.pause
+    }
+}
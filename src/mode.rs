@@ -0,0 +1,108 @@
+use std::fmt;
+
+use config::GameConfig;
+
+
+// How long a Time Attack run lasts, in seconds.
+pub const TIME_ATTACK_SECONDS: f32 = 60.0;
+
+// Hardcore's tweaks to the [scroll] section.
+const HARDCORE_GAP_SCALE: f32 = 0.8;
+const HARDCORE_SPEED_SCALE: f32 = 1.25;
+
+// The ruleset a run is played under: how it ends, what counts towards the
+// score and what turns up along the way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameMode {
+    Classic,
+    // No dying; for practice.
+    Zen,
+    // As many pipes as possible before the clock runs out.
+    TimeAttack,
+    // Narrower gaps, faster pipes and no help along the way.
+    Hardcore,
+    // Classic on a layout picked by the date, with one scored attempt a day.
+    Daily,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 5] = [
+        GameMode::Classic,
+        GameMode::Zen,
+        GameMode::TimeAttack,
+        GameMode::Hardcore,
+        GameMode::Daily,
+    ];
+
+    // Name used on the command line, in replays and in file names.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            GameMode::Classic => "classic",
+            GameMode::Zen => "zen",
+            GameMode::TimeAttack => "time-attack",
+            GameMode::Hardcore => "hardcore",
+            GameMode::Daily => "daily",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        GameMode::ALL.iter().cloned().find(|mode| mode.name() == name)
+    }
+
+    // The mode after this one, for cycling through them in the menu.
+    pub fn next(&self) -> Self {
+        let index = GameMode::ALL.iter().position(|mode| mode == self).unwrap();
+        GameMode::ALL[(index + 1) % GameMode::ALL.len()]
+    }
+
+    // Whether hitting a pipe or the ground ends the run.
+    pub fn can_die(&self) -> bool {
+        *self != GameMode::Zen
+    }
+
+    // How long a run lasts before it ends on its own, in seconds.
+    pub fn time_limit(&self) -> Option<f32> {
+        match *self {
+            GameMode::TimeAttack => Some(TIME_ATTACK_SECONDS),
+            _ => None,
+        }
+    }
+
+    // Whether runs go in a high score table. Zen isn't scored, and Daily
+    // keeps its results by day instead.
+    pub fn keeps_high_scores(&self) -> bool {
+        !matches!(*self, GameMode::Zen | GameMode::Daily)
+    }
+
+    // The config a run in this mode plays with, given the one loaded from
+    // disk.
+    pub fn apply(&self, config: &GameConfig) -> GameConfig {
+        let mut config = config.clone();
+        match *self {
+            GameMode::Classic | GameMode::Daily => {},
+            GameMode::Zen => {
+                // Nothing to score and nothing to survive.
+                config.pickups.coin_chance = 0.0;
+                config.pickups.shield_chance = 0.0;
+            },
+            GameMode::TimeAttack => {
+                // Only pipes count, and slowing down would only cost time.
+                config.pickups.coin_chance = 0.0;
+                config.pickups.slow_motion_chance = 0.0;
+            },
+            GameMode::Hardcore => {
+                config.scroll.vertical_gap *= HARDCORE_GAP_SCALE;
+                config.scroll.speed *= HARDCORE_SPEED_SCALE;
+                config.pickups.shield_chance = 0.0;
+                config.pickups.slow_motion_chance = 0.0;
+            },
+        }
+        config
+    }
+}
+
+impl fmt::Display for GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
@@ -0,0 +1,101 @@
+use cgmath::{self, Vector2};
+use nalgebra::{self, Isometry2};
+use ncollide::query;
+use ncollide::shape::Ball;
+use rand::Rng;
+
+use bird::Bird;
+use config::PickupConfig;
+use rng::GameRng;
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PickupKind {
+    // Worth extra points.
+    Coin,
+    // Lets the bird survive hitting one pipe.
+    Shield,
+    // Slows the game down for a while.
+    SlowMotion,
+}
+
+impl PickupKind {
+    // Picks what, if anything, turns up after a pipe.
+    pub fn roll(config: &PickupConfig, rng: &mut GameRng) -> Option<PickupKind> {
+        let roll: f32 = rng.gen();
+        if roll < config.coin_chance {
+            Some(PickupKind::Coin)
+        } else if roll < config.coin_chance + config.shield_chance {
+            Some(PickupKind::Shield)
+        } else if roll < config.coin_chance + config.shield_chance + config.slow_motion_chance {
+            Some(PickupKind::SlowMotion)
+        } else {
+            None
+        }
+    }
+
+    pub fn color(&self) -> [f32; 3] {
+        match *self {
+            PickupKind::Coin => [1.0, 215.0 / 255.0, 0.0],
+            PickupKind::Shield => [90.0 / 255.0, 170.0 / 255.0, 1.0],
+            PickupKind::SlowMotion => [190.0 / 255.0, 110.0 / 255.0, 230.0 / 255.0],
+        }
+    }
+}
+
+// A pickup riding along with a pipe, placed relative to it.
+pub struct Pickup {
+    kind: PickupKind,
+    offset: Vector2<f32>,
+    shape: Ball<f32>,
+    collected: bool,
+}
+
+impl Pickup {
+    pub fn new(kind: PickupKind, offset: Vector2<f32>, radius: f32) -> Self {
+        Pickup {
+            kind,
+            offset,
+            shape: Ball::new(radius),
+            collected: false,
+        }
+    }
+
+    // Whether the bird, with its owner at `origin`, touches the pickup.
+    pub fn touches(&self, origin: Vector2<f32>, bird: &Bird) -> bool {
+        let (bounding_circle, bird_center) = bird.bounding_circle();
+        let bird_center = Isometry2::new(bird_center, nalgebra::zero());
+
+        let center = self.center(origin);
+        let center = Isometry2::new(nalgebra::Vector2::new(center.x, center.y), nalgebra::zero());
+
+        query::distance(&bird_center, bounding_circle, &center, &self.shape) == 0.0
+    }
+
+    pub fn center(&self, origin: Vector2<f32>) -> Vector2<f32> {
+        origin + self.offset
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.shape.radius()
+    }
+
+    pub fn kind(&self) -> PickupKind {
+        self.kind
+    }
+
+    pub fn is_collected(&self) -> bool {
+        self.collected
+    }
+
+    pub fn collect(&mut self) {
+        self.collected = true;
+    }
+}
+
+// Where a pickup goes relative to the pipe it follows: halfway to the next
+// pipe, near the height of the pipe's gap.
+pub fn pickup_offset(pipe_width: f32, spacing: f32, gap_center: f32, rng: &mut GameRng) -> Vector2<f32> {
+    let jitter = (rng.gen::<f32>() - 0.5) * 24.0;
+    cgmath::vec2(pipe_width + spacing / 2.0, gap_center + jitter)
+}
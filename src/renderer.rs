@@ -1,262 +1,744 @@
-use std::fs::File;
-use std::rc::Rc;
+use std::f32::consts::PI;
 
-use bmfont::{BMFont, OrdinateOrientation};
 use cgmath;
-use midgar::{Midgar, Surface, Texture2d};
-use midgar::graphics::animation::{Animation, PlayMode};
+use midgar::{Midgar, Surface};
 use midgar::graphics::shape::ShapeRenderer;
 use midgar::graphics::sprite::{DrawTexture, MagnifySamplerFilter, Sprite, SpriteDrawParams, SpriteRenderer};
-use midgar::graphics::texture::{TextureRegion, TextureRegionHolder};
-
-use scroll_handler::Pipe;
-use world::{GameState, GameWorld};
+use midgar::graphics::texture::TextureRegionHolder;
+
+use assets::Assets;
+use bird::Bird;
+use config::GAME_WIDTH;
+use daily::DailyResults;
+use date::Date;
+use evolution::Evolution;
+use highscores::{HighScoreTable, MAX_HIGH_SCORES};
+use input::MAX_PLAYERS;
+use medal::Medal;
+use pickup::PickupKind;
+use menu::{Menu, MenuAction, MenuScreen, Menus};
+use scroll_handler::{Pipe, ScrollHandler};
+use world::{self, GameState, GameWorld};
 
 
 const CLEAR_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+// Middle of the game, across.
+const CENTER_X: f32 = GAME_WIDTH / 2.0;
+const SCORE_SCALE: f32 = 0.25;
+const MEDIUM_TEXT_SCALE: f32 = 0.1875;
+const SMALL_TEXT_SCALE: f32 = 0.125;
+// How long the game over panel takes to slide in.
+const PANEL_SLIDE_TIME: f32 = 0.4;
+// How many high scores the game over panel has room for.
+const PANEL_HIGH_SCORES: usize = 3;
+// Opacity of the ghost of an earlier run.
+const GHOST_ALPHA: f32 = 0.4;
+// Colour each player's bird is tinted, in player order. The first keeps the
+// bird's own colours.
+const PLAYER_TINTS: [[f32; 3]; MAX_PLAYERS] = [
+    [1.0, 1.0, 1.0],
+    [0.6, 1.0, 0.6],
+    [1.0, 0.6, 0.6],
+    [0.6, 0.8, 1.0],
+];
+// Debug overlay colours: collision boxes, the birds' bounding circles, their
+// velocity, and pipes that have and haven't been scored.
+const DEBUG_BOX_COLOR: [f32; 3] = [1.0, 0.0, 1.0];
+const DEBUG_BIRD_COLOR: [f32; 3] = [1.0, 1.0, 0.0];
+const DEBUG_VELOCITY_COLOR: [f32; 3] = [0.0, 1.0, 1.0];
+const DEBUG_SCORED_COLOR: [f32; 3] = [0.0, 1.0, 0.0];
+const DEBUG_UNSCORED_COLOR: [f32; 3] = [1.0, 0.0, 0.0];
+// Thickness of the overlay's lines.
+const DEBUG_LINE_WIDTH: f32 = 0.5;
+// Seconds of flight each velocity line shows.
+const DEBUG_VELOCITY_SCALE: f32 = 0.1;
+
+
+// The scores shown around the game, which depend on the mode.
+pub enum Scoreboard<'s> {
+    HighScores(&'s HighScoreTable),
+    Daily {
+        results: &'s DailyResults,
+        date: Date,
+        // Whether the run on screen is the day's scored attempt rather than
+        // practice.
+        scored: bool,
+    },
+}
 
+// How fast frames are going, for the debug overlay.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameStats {
+    pub fps: f64,
+    // In seconds.
+    pub frame_time: f64,
+}
 
 pub struct GameRenderer<'a> {
     sprite_renderer: SpriteRenderer,
     shape_renderer: ShapeRenderer,
 
-    texture: Rc<Texture2d>,
-    // logoTexture: Rc<Texture2d>,
-    text_texture: Rc<Texture2d>,
-    shadow_texture: Rc<Texture2d>,
-
-    text_font: BMFont,
-    shadow_font: BMFont,
-
-    bird: TextureRegion,
-    bird_up: TextureRegion,
-    bird_down: TextureRegion,
-    bird_animation: Animation,
+    assets: Assets<'a>,
 
-    // TODO: Make these TextureRegions since we don't need to store state with them.
-    bg: Sprite<'a>,
-    grass: Sprite<'a>,
-    skull_up: Sprite<'a>,
-    skull_down: Sprite<'a>,
-    bar: TextureRegion,
-
-    // TextureRegion logo, zbLogo;
-    // TextureRegion playButtonUp, playButtonDown;
+    // Where each entry of the last menu drawn went, as (x, y, width, height)
+    // in game coordinates, so clicks can be matched up with what's on screen.
+    menu_rects: Vec<(f32, f32, f32, f32)>,
 }
 
 impl<'a> GameRenderer<'a> {
     pub fn new(midgar: &Midgar) -> Self {
         let (screen_width, screen_height) = midgar.graphics().screen_size();
-        let game_width = 136.0f32;
-        let game_height = screen_height as f32 / (screen_width as f32 / game_width);
-        let mid_point_y = (game_height / 2.0) as u32;
-
-        let texture = midgar.graphics().load_texture("assets/texture.png", true);
-        let texture = Rc::new(texture);
-
-        let text_texture = midgar.graphics().load_texture("assets/text.png", false);
-        let text_texture = Rc::new(text_texture);
-        let shadow_texture = midgar.graphics().load_texture("assets/shadow.png", false);
-        let shadow_texture = Rc::new(shadow_texture);
+        let game_height = screen_height as f32 / (screen_width as f32 / GAME_WIDTH);
 
-        let text_font = {
-            let file = File::open("assets/text.fnt").unwrap();
-            BMFont::new(file, OrdinateOrientation::TopToBottom).unwrap()
-        };
-        let shadow_font = {
-            let file = File::open("assets/shadow.fnt").unwrap();
-            BMFont::new(file, OrdinateOrientation::TopToBottom).unwrap()
-        };
+        let assets = Assets::load(midgar).unwrap();
 
-        // Load bird.
-        let bird = TextureRegion::with_sub_field(texture.clone(), (153, 116), (17, 12));
-        let bird_up = TextureRegion::with_sub_field(texture.clone(), (170, 116), (17, 12));
-        let bird_down = TextureRegion::with_sub_field(texture.clone(), (136, 116), (17, 12));
-
-        let mut bird_animation = Animation::new(0.06, &[bird_down.clone(), bird.clone(), bird_up.clone()]).unwrap();
-        bird_animation.play_mode = PlayMode::LoopPingPong;
-
-        // Load background.
-        let mut bg = Sprite::with_sub_field(texture.clone(), (0, 85), (136, 43));
-        bg.set_position(cgmath::vec2(0.0, mid_point_y as f32 - 66.0));
-
-        // Load grass.
-        let grass = Sprite::with_sub_field(texture.clone(), (0, 74), (143, 11));
-
-        // Load pipe.
-        let skull_up = Sprite::with_sub_field(texture.clone(), (192, 114), (24, 14));
-        let mut skull_down = Sprite::with_sub_field(texture.clone(), (192, 114), (24, 14));
-        skull_down.set_flip_y(true);
-        let bar = TextureRegion::with_sub_field(texture.clone(), (136, 109), (22, 3));
-
-        let projection = cgmath::ortho(0.0, game_width, 0.0, game_height, -1.0, 1.0);
+        let projection = cgmath::ortho(0.0, GAME_WIDTH, 0.0, game_height, -1.0, 1.0);
 
         GameRenderer {
             sprite_renderer: SpriteRenderer::new(midgar.graphics().display(), projection),
             shape_renderer: ShapeRenderer::new(midgar.graphics().display(), projection),
 
-            texture,
-            text_texture,
-            shadow_texture,
+            assets,
 
-            text_font,
-            shadow_font,
-
-            bird,
-            bird_up,
-            bird_down,
-            bird_animation,
-
-            bg,
-            grass,
-            skull_up,
-            skull_down,
-            bar,
+            menu_rects: Vec::new(),
         }
     }
 
-    pub fn render(&mut self, midgar: &Midgar, dt: f32, world: &GameWorld) {
+    // Loads the assets again, keeping the current ones if that fails.
+    pub fn reload_assets(&mut self, midgar: &Midgar) -> Result<(), String> {
+        self.assets = Assets::load(midgar)?;
+        Ok(())
+    }
+
+    // `alpha` is how far we are between the world's last tick and the next
+    // one, in [0, 1). Moving objects are drawn interpolated by it. `autopilot`
+    // is whether the autopilot is flying, which the score line says. The debug
+    // overlay goes on top if `debug` is given.
+    pub fn render(&mut self, midgar: &Midgar, alpha: f32, world: &GameWorld, scores: &Scoreboard,
+                  menus: &Menus, autopilot: bool, debug: Option<&FrameStats>) {
         // Get framebuffer target.
         let mut target = midgar.graphics().display().draw();
         target.clear_color(CLEAR_COLOR[0], CLEAR_COLOR[1], CLEAR_COLOR[2], CLEAR_COLOR[3]);
+        self.menu_rects.clear();
 
-        // Draw Background color
-        let color = [55.0 / 255.0, 80.0 / 255.0, 100.0 / 255.0];
-        self.shape_renderer.draw_filled_rect(0.0, world.mid_point_y() as f32 - 23.0, 136.0, world.mid_point_y() as f32 + 23.0,
-                                             color, &mut target);
-
-        // Draw Dirt
-        let color = [147.0 / 255.0, 80.0 / 255.0, 27.0 / 255.0];
-        self.shape_renderer.draw_filled_rect(0.0, 0.0, 136.0, 52.0, color, &mut target);
-
-        // Draw world background.
-        let draw_params = SpriteDrawParams::new().magnify_filter(MagnifySamplerFilter::Nearest);
-        self.sprite_renderer.draw(&self.bg, draw_params, &mut target);
-
-        // Draw grass and pipes.
-        self.draw_grass(world, &mut target);
-        self.draw_pipes(world, &mut target);
-        self.draw_skulls(world, &mut target);
+        self.draw_scenery(world.mid_point_y(), world.scroller(), alpha, &mut target);
 
         // Draw world.
         match world.game_state() {
             GameState::Running => {
-                self.draw_bird(world, &mut target);
-                self.draw_score(world, &mut target);
+                self.draw_ghost(world, alpha, &mut target);
+                self.draw_birds(world, alpha, &mut target);
+                self.draw_score(world, scores, autopilot, &mut target);
             },
             GameState::Ready => {
-                self.draw_bird(world, &mut target);
-                self.draw_score(world, &mut target);
+                self.draw_birds(world, alpha, &mut target);
+                self.draw_score(world, scores, autopilot, &mut target);
             },
             GameState::Menu => {
+                self.draw_menus(world, alpha, scores, menus, &mut target);
             },
-            GameState::GameOver => {
-                self.draw_bird(world, &mut target);
-                self.draw_score(world, &mut target);
+            GameState::GameOver | GameState::HighScore => {
+                self.draw_ghost(world, alpha, &mut target);
+                self.draw_birds(world, alpha, &mut target);
+                self.draw_game_over(world, scores, menus, &mut target);
             },
-            GameState::HighScore => {
-                self.draw_bird(world, &mut target);
-                self.draw_score(world, &mut target);
+            GameState::Paused => {
+                self.draw_ghost(world, alpha, &mut target);
+                self.draw_birds(world, alpha, &mut target);
+                self.draw_score(world, scores, autopilot, &mut target);
+                self.draw_pause(world, menus, &mut target);
             },
         }
 
+        if let Some(stats) = debug {
+            self.draw_debug(world, stats, &mut target);
+        }
+
+        target.finish().unwrap();
+    }
+
+    // Shows what the physics sees as of the last tick, uninterpolated: the
+    // collision shapes in outline, each bird's velocity, a flag in each
+    // pipe's gap for whether it's been scored, and the state of the game.
+    fn draw_debug<S: Surface>(&mut self, world: &GameWorld, stats: &FrameStats, target: &mut S) {
+        let (ground_center, ground_half_extents) = world::ground_box(world.mid_point_y());
+        self.draw_box_outline((ground_center.x, ground_center.y), (ground_half_extents.x, ground_half_extents.y),
+                              DEBUG_BOX_COLOR, target);
+
+        for pipe in world.scroller().pipes() {
+            let half_width = pipe.width() as f32 / 2.0;
+            let [lower, upper] = pipe.bar_centers();
+            self.draw_box_outline((lower.x, lower.y), (half_width, pipe.lower_bar_height() / 2.0),
+                                  DEBUG_BOX_COLOR, target);
+            // An alpha of 1 gives the bar as of the last tick, like the rest.
+            self.draw_box_outline((upper.x, upper.y), (half_width, pipe.interpolated_upper_bar_height(1.0) / 2.0),
+                                  DEBUG_BOX_COLOR, target);
+
+            let color = if pipe.is_scored() { DEBUG_SCORED_COLOR } else { DEBUG_UNSCORED_COLOR };
+            let flag_y = pipe.position().y + pipe.height() + pipe.vertical_gap() / 2.0;
+            self.shape_renderer.draw_filled_rect(lower.x - 1.5, flag_y - 1.5, 3.0, 3.0, color, target);
+        }
+
+        for player in world.players() {
+            let bird = player.bird();
+            let (bounding_circle, center) = bird.bounding_circle();
+            self.draw_circle_outline((center.x, center.y), bounding_circle.radius(), DEBUG_BIRD_COLOR, target);
+            let velocity = bird.velocity() * DEBUG_VELOCITY_SCALE;
+            self.draw_line((center.x, center.y), (center.x + velocity.x, center.y + velocity.y),
+                           DEBUG_VELOCITY_COLOR, target);
+        }
+
+        let state = format!("{:?}", world.game_state()).to_lowercase();
+        let lines = [
+            format!("{} tick {}", state, world.run_tick()),
+            format!("vy {:.0}", world.bird().velocity().y),
+            format!("fps {:.0} {:.1}ms", stats.fps, stats.frame_time * 1000.0),
+        ];
+        let top = world.mid_point_y() as f32 * 2.0;
+        for (i, line) in lines.iter().enumerate() {
+            self.draw_text(line, (2.0, top - 8.0 * (i + 1) as f32), SMALL_TEXT_SCALE, target);
+        }
+    }
+
+    // The overlay's shapes are drawn out of thin filled rectangles.
+    fn draw_box_outline<S: Surface>(&mut self, center: (f32, f32), half_extents: (f32, f32), color: [f32; 3],
+                                    target: &mut S) {
+        let (left, bottom) = (center.0 - half_extents.0, center.1 - half_extents.1);
+        let (width, height) = (half_extents.0 * 2.0, half_extents.1 * 2.0);
+        let line = DEBUG_LINE_WIDTH;
+        self.shape_renderer.draw_filled_rect(left, bottom, width, line, color, target);
+        self.shape_renderer.draw_filled_rect(left, bottom + height - line, width, line, color, target);
+        self.shape_renderer.draw_filled_rect(left, bottom, line, height, color, target);
+        self.shape_renderer.draw_filled_rect(left + width - line, bottom, line, height, color, target);
+    }
+
+    fn draw_circle_outline<S: Surface>(&mut self, center: (f32, f32), radius: f32, color: [f32; 3],
+                                       target: &mut S) {
+        let segments = 24;
+        let point = |i: u32| {
+            let angle = 2.0 * PI * i as f32 / segments as f32;
+            (center.0 + radius * angle.cos(), center.1 + radius * angle.sin())
+        };
+        for i in 0..segments {
+            self.draw_line(point(i), point(i + 1), color, target);
+        }
+    }
+
+    // Dots a line from one point to the other.
+    fn draw_line<S: Surface>(&mut self, from: (f32, f32), to: (f32, f32), color: [f32; 3], target: &mut S) {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let steps = ((dx * dx + dy * dy).sqrt() / DEBUG_LINE_WIDTH).ceil().max(1.0) as u32;
+        let half = DEBUG_LINE_WIDTH / 2.0;
+        for i in 0..=steps {
+            let t = i as f32 / steps as f32;
+            self.shape_renderer.draw_filled_rect(from.0 + dx * t - half, from.1 + dy * t - half,
+                                                 DEBUG_LINE_WIDTH, DEBUG_LINE_WIDTH, color, target);
+        }
+    }
+
+    // Draws a flock of birds learning to fly, with how the training is
+    // going in place of the score.
+    pub fn render_evolution(&mut self, midgar: &Midgar, alpha: f32, evolution: &Evolution) {
+        let mut target = midgar.graphics().display().draw();
+        target.clear_color(CLEAR_COLOR[0], CLEAR_COLOR[1], CLEAR_COLOR[2], CLEAR_COLOR[3]);
+
+        let mid_point_y = evolution.mid_point_y();
+        self.draw_scenery(mid_point_y, evolution.scroller(), alpha, &mut target);
+
+        for member in evolution.members().iter().filter(|member| member.bird().is_alive()) {
+            let bird = member.bird();
+            let position = bird.interpolated_position(alpha);
+            self.draw_bird_at(bird, position, alpha, evolution.flight_time(), PLAYER_TINTS[0], &mut target);
+        }
+
+        let top = mid_point_y as f32 * 2.0;
+        let generation_text = format!("gen {}", evolution.generation() + 1);
+        self.draw_text(&generation_text, (4.0, top - 10.0), SMALL_TEXT_SCALE, &mut target);
+        let best_text = format!("best {:.0}", evolution.best_fitness());
+        self.draw_text(&best_text, (4.0, top - 18.0), SMALL_TEXT_SCALE, &mut target);
+        let alive_text = format!("alive {}/{}", evolution.alive(), evolution.members().len());
+        self.draw_text(&alive_text, (4.0, top - 26.0), SMALL_TEXT_SCALE, &mut target);
+        let score_text = evolution.score().to_string();
+        self.draw_text_centered(&score_text, CENTER_X, mid_point_y as f32 + 65.0, SCORE_SCALE, &mut target);
+
         target.finish().unwrap();
     }
 
+    // The entry of the menu on screen at `point`, in game coordinates.
+    pub fn menu_entry_at(&self, point: (f32, f32)) -> Option<usize> {
+        let (x, y) = point;
+        self.menu_rects.iter()
+            .position(|&(left, bottom, width, height)| {
+                x >= left && x < left + width && y >= bottom && y < bottom + height
+            })
+    }
+
     pub fn resize(&mut self, size: (u32, u32)) {
-        let game_width = 136.0f32;
-        let game_height = size.1 as f32 / (size.0 as f32 / game_width);
-        let projection = cgmath::ortho(0.0, game_width, 0.0, game_height, -1.0, 1.0);
+        let game_height = size.1 as f32 / (size.0 as f32 / GAME_WIDTH);
+        let projection = cgmath::ortho(0.0, GAME_WIDTH, 0.0, game_height, -1.0, 1.0);
 
         self.sprite_renderer.set_projection_matrix(projection);
         self.shape_renderer.set_projection_matrix(projection);
     }
 
-    fn draw_bird<S: Surface>(&mut self, world: &GameWorld, target: &mut S) {
-        let position = world.bird().position();
-        let rotation = world.bird().rotation();
-        let texture = if world.bird().should_flap() {
-            self.bird_animation.current_key_frame(world.run_time())
+    fn draw_birds<S: Surface>(&mut self, world: &GameWorld, alpha: f32, target: &mut S) {
+        for (i, player) in world.players().iter().enumerate() {
+            let position = player.bird().interpolated_position(alpha);
+
+            // Show the shield as a glow behind the bird.
+            if player.shield_time() > 0.0 {
+                let color = PickupKind::Shield.color();
+                self.shape_renderer.draw_filled_rect(position.x - 2.0, position.y - 2.0, 21.0, 16.0,
+                                                     color, target);
+            }
+
+            self.draw_bird_at(player.bird(), position, alpha, world.run_time(), PLAYER_TINTS[i], target);
+        }
+
+        // Slow motion gets a bar across the top showing how long it has left.
+        if world.slow_motion_time() > 0.0 {
+            let left = world.slow_motion_time() / world.config().pickups.slow_motion_time;
+            let top = world.mid_point_y() as f32 * 2.0;
+            let color = PickupKind::SlowMotion.color();
+            self.shape_renderer.draw_filled_rect(0.0, top - 2.0, GAME_WIDTH * left, 2.0, color, target);
+        }
+    }
+
+    // Draws an earlier run's bird, faded, where it was at this point.
+    fn draw_ghost<S: Surface>(&mut self, world: &GameWorld, alpha: f32, target: &mut S) {
+        let frame = match world.ghost_frame(alpha) {
+            Some(frame) => frame,
+            None => return,
+        };
+
+        let x = world.bird().position().x;
+        let mut sprite = self.assets.bird.draw(x, frame.y);
+        sprite.set_rotation(frame.rotation);
+        sprite.set_alpha(GHOST_ALPHA);
+        let draw_params = SpriteDrawParams::new()
+            .alpha(true)
+            .magnify_filter(MagnifySamplerFilter::Nearest);
+        self.sprite_renderer.draw(&sprite, draw_params, target);
+    }
+
+    fn draw_bird_at<S: Surface>(&mut self, bird: &Bird, position: cgmath::Vector2<f32>, alpha: f32,
+                                run_time: f32, tint: [f32; 3], target: &mut S) {
+        let rotation = bird.interpolated_rotation(alpha);
+        let texture = if bird.should_flap() {
+            self.assets.bird_animation.current_key_frame(run_time)
         } else {
-            &self.bird
+            &self.assets.bird
         };
 
         let mut sprite = texture.draw(position.x, position.y);
         sprite.set_rotation(rotation);
+        sprite.set_color(cgmath::vec3(tint[0], tint[1], tint[2]));
         let draw_params = SpriteDrawParams::new()
             .alpha(true)
             .magnify_filter(MagnifySamplerFilter::Nearest);
         self.sprite_renderer.draw(&sprite, draw_params, target);
     }
 
-    fn draw_grass<S: Surface>(&mut self, world: &GameWorld, target: &mut S) {
+    // Draws everything behind the birds: the sky, the ground and the
+    // scroller's grass, pipes and pickups.
+    fn draw_scenery<S: Surface>(&mut self, mid_point_y: u32, scroller: &ScrollHandler, alpha: f32,
+                                target: &mut S) {
+        // Draw Background color
+        let color = [55.0 / 255.0, 80.0 / 255.0, 100.0 / 255.0];
+        self.shape_renderer.draw_filled_rect(0.0, mid_point_y as f32 - 23.0, GAME_WIDTH, mid_point_y as f32 + 23.0,
+                                             color, target);
+
+        // Draw Dirt
+        let color = [147.0 / 255.0, 80.0 / 255.0, 27.0 / 255.0];
+        self.shape_renderer.draw_filled_rect(0.0, 0.0, GAME_WIDTH, 52.0, color, target);
+
+        // Draw world background.
         let draw_params = SpriteDrawParams::new().magnify_filter(MagnifySamplerFilter::Nearest);
-        self.grass.set_position(world.scroller().front_grass().position());
-        self.sprite_renderer.draw(&self.grass, draw_params, target);
-        self.grass.set_position(world.scroller().back_grass().position());
-        self.sprite_renderer.draw(&self.grass, draw_params, target);
+        self.sprite_renderer.draw(&self.assets.bg, draw_params, target);
+
+        // Draw grass and pipes.
+        self.draw_grass(scroller, alpha, target);
+        self.draw_pipes(scroller, alpha, target);
+        self.draw_skulls(scroller, alpha, target);
+        self.draw_pickups(scroller, alpha, target);
     }
 
-    fn draw_skulls<S: Surface>(&mut self, world: &GameWorld, target: &mut S) {
+    fn draw_grass<S: Surface>(&mut self, scroller: &ScrollHandler, alpha: f32, target: &mut S) {
         let draw_params = SpriteDrawParams::new().magnify_filter(MagnifySamplerFilter::Nearest);
-        let mut draw_skull = |pipe: &Pipe| {
-            let position = pipe.position();
-            let height = pipe.height();
+        self.assets.grass.set_position(scroller.front_grass().interpolated_position(alpha));
+        self.sprite_renderer.draw(&self.assets.grass, draw_params, target);
+        self.assets.grass.set_position(scroller.back_grass().interpolated_position(alpha));
+        self.sprite_renderer.draw(&self.assets.grass, draw_params, target);
+    }
 
-            self.skull_up.set_position(position + cgmath::vec2(-1.0, height as f32 - 14.0));
-            self.sprite_renderer.draw(&self.skull_up, draw_params, target);
-            self.skull_down.set_position(position + cgmath::vec2(-1.0, 45.0 + height as f32));
-            self.sprite_renderer.draw(&self.skull_down, draw_params, target);
+    fn draw_skulls<S: Surface>(&mut self, scroller: &ScrollHandler, alpha: f32, target: &mut S) {
+        let draw_params = SpriteDrawParams::new().magnify_filter(MagnifySamplerFilter::Nearest);
+        let mut draw_skull = |pipe: &Pipe| {
+            let position = pipe.interpolated_position(alpha);
+            let height = pipe.interpolated_height(alpha);
+            let gap = pipe.interpolated_vertical_gap(alpha);
+
+            self.assets.skull_up.set_position(position + cgmath::vec2(-1.0, height - 14.0));
+            self.sprite_renderer.draw(&self.assets.skull_up, draw_params, target);
+            self.assets.skull_down.set_position(position + cgmath::vec2(-1.0, gap + height));
+            self.sprite_renderer.draw(&self.assets.skull_down, draw_params, target);
         };
 
-        draw_skull(world.scroller().pipe1());
-        draw_skull(world.scroller().pipe2());
-        draw_skull(world.scroller().pipe3());
+        for pipe in scroller.pipes() {
+            draw_skull(pipe);
+        }
     }
 
-    fn draw_pipes<S: Surface>(&mut self, world: &GameWorld, target: &mut S) {
+    fn draw_pipes<S: Surface>(&mut self, scroller: &ScrollHandler, alpha: f32, target: &mut S) {
         let draw_params = SpriteDrawParams::new().magnify_filter(MagnifySamplerFilter::Nearest);
         let mut draw_pipe = |pipe: &Pipe| {
-            let position = pipe.position();
+            let position = pipe.interpolated_position(alpha);
             let width = pipe.width();
-            let height = pipe.height();
-            let pipe_texture_height = self.bar.size().y as f32;
+            let height = pipe.interpolated_height(alpha);
+            let gap = pipe.interpolated_vertical_gap(alpha);
+            let pipe_texture_height = self.assets.bar.size().y as f32;
 
             // TODO: Set width/height on a sprite to extend it before scaling it.
 
-            let mut sprite = self.bar.draw(position.x, position.y);
-            sprite.set_scale(cgmath::vec2(1.0, pipe.lower_bar_height() / pipe_texture_height));
+            let mut sprite = self.assets.bar.draw(position.x, position.y);
+            sprite.set_scale(cgmath::vec2(1.0, height / pipe_texture_height));
             self.sprite_renderer.draw(&sprite, draw_params, target);
-            sprite.set_position(cgmath::vec2(position.x, position.y + height as f32 + 45.0));
-            sprite.set_scale(cgmath::vec2(1.0, pipe.upper_bar_height() / pipe_texture_height));
+            sprite.set_position(cgmath::vec2(position.x, position.y + height + gap));
+            sprite.set_scale(cgmath::vec2(1.0, pipe.interpolated_upper_bar_height(alpha) / pipe_texture_height));
             self.sprite_renderer.draw(&sprite, draw_params, target);
         };
 
-        draw_pipe(world.scroller().pipe1());
-        draw_pipe(world.scroller().pipe2());
-        draw_pipe(world.scroller().pipe3());
+        for pipe in scroller.pipes() {
+            draw_pipe(pipe);
+        }
     }
 
-    fn draw_score<S: Surface>(&mut self, world: &GameWorld, target: &mut S) {
-        let scale = 0.25;
+    fn draw_pickups<S: Surface>(&mut self, scroller: &ScrollHandler, alpha: f32, target: &mut S) {
+        for pipe in scroller.pipes() {
+            if let Some(pickup) = pipe.pickup() {
+                let center = pickup.center(pipe.interpolated_position(alpha));
+                let radius = pickup.radius();
+                self.shape_renderer.draw_filled_rect(center.x - radius, center.y - radius,
+                                                     radius * 2.0, radius * 2.0,
+                                                     pickup.kind().color(), target);
+            }
+        }
+    }
+
+    fn draw_score<S: Surface>(&mut self, world: &GameWorld, scores: &Scoreboard, autopilot: bool,
+                              target: &mut S) {
+        let players = world.players();
+        let position = if players.len() > 1 {
+            // Each player's score gets a column, underlined in their colour.
+            let y = world.mid_point_y() as f32 + 65.0;
+            let column_width = GAME_WIDTH / players.len() as f32;
+            for (i, player) in players.iter().enumerate() {
+                let center_x = column_width * (i as f32 + 0.5);
+                self.shape_renderer.draw_filled_rect(center_x - 5.0, y - 3.0, 10.0, 2.0, PLAYER_TINTS[i],
+                                                     target);
+                self.draw_text_centered(&player.score().to_string(), center_x, y, MEDIUM_TEXT_SCALE, target);
+            }
+            (CENTER_X, y)
+        } else {
+            let score_text: String = world.score().to_string();
+            //let score_text = format!("{:02}", world.score());
+            let text_length = score_text.len();
+
+            let position = (CENTER_X - (3.0 * text_length as f32), world.mid_point_y() as f32 + 65.0);
+            self.draw_text(&score_text, position, SCORE_SCALE, target);
+            position
+        };
+
+        // Timed modes count down under the score.
+        if let Some(time_left) = world.time_left() {
+            let time_text = (time_left.ceil() as u32).to_string();
+            self.draw_text_centered(&time_text, CENTER_X, position.1 - 10.0, SMALL_TEXT_SCALE, target);
+        }
+        if let Scoreboard::Daily { scored: false, .. } = *scores {
+            self.draw_text_centered("practice", CENTER_X, position.1 - 10.0, SMALL_TEXT_SCALE, target);
+        }
+        if autopilot {
+            self.draw_text_centered("autopilot", CENTER_X, position.1 - 18.0, SMALL_TEXT_SCALE, target);
+        }
+    }
+
+    // Draws the score card, which slides up from the bottom once the run ends.
+    fn draw_game_over<S: Surface>(&mut self, world: &GameWorld, scores: &Scoreboard,
+                                  menus: &Menus, target: &mut S) {
+        let mid_point_y = world.mid_point_y() as f32;
+        let slide = 1.0 - (world.game_over_time() / PANEL_SLIDE_TIME).min(1.0);
+        let offset = slide * slide * slide * (mid_point_y + 60.0);
+        let top = mid_point_y + 56.0 - offset;
+        let bottom = mid_point_y - 60.0 - offset;
+
+        // Draw the panel with a border.
+        let border_color = [83.0 / 255.0, 56.0 / 255.0, 71.0 / 255.0];
+        let panel_color = [222.0 / 255.0, 216.0 / 255.0, 149.0 / 255.0];
+        self.shape_renderer.draw_filled_rect(10.0, bottom, 116.0, top - bottom, border_color, target);
+        self.shape_renderer.draw_filled_rect(12.0, bottom + 2.0, 112.0, top - bottom - 4.0, panel_color, target);
+
+        let new_best = world.game_state() == GameState::HighScore;
+        let practice = matches!(*scores, Scoreboard::Daily { scored: false, .. });
+        let players = world.players();
+        let title = if practice {
+            "practice".to_string()
+        } else if players.len() > 1 {
+            let winners: Vec<usize> = players.iter().enumerate()
+                .filter(|&(_, player)| player.score() == world.score())
+                .map(|(i, _)| i)
+                .collect();
+            match winners[..] {
+                [winner] => format!("p{} wins!", winner + 1),
+                _ => "tie!".to_string(),
+            }
+        } else if new_best {
+            "new best!".to_string()
+        } else if world.time_left() == Some(0.0) {
+            "time up".to_string()
+        } else {
+            "game over".to_string()
+        };
+        self.draw_text_centered(&title, CENTER_X, top - 16.0, MEDIUM_TEXT_SCALE, target);
+
+        let score_text = format!("score {}", world.score());
+        self.draw_text(&score_text, (18.0, top - 28.0), SMALL_TEXT_SCALE, target);
+        let best_text = format!("best {}", world.high_score());
+        self.draw_text(&best_text, (18.0, top - 38.0), SMALL_TEXT_SCALE, target);
+
+        // Draw the medal as a swatch next to its name.
+        let medal_y = top - 50.0;
+        match Medal::for_score(world.score(), &world.config().medals) {
+            Some(medal) => {
+                self.shape_renderer.draw_filled_rect(18.0, medal_y, 8.0, 8.0, medal.color(), target);
+                let medal_text = format!("{} medal", medal.name());
+                self.draw_text(&medal_text, (30.0, medal_y), SMALL_TEXT_SCALE, target);
+            },
+            None => self.draw_text("no medal", (18.0, medal_y), SMALL_TEXT_SCALE, target),
+        }
+
+        let seed_text = format!("seed {}", world.seed());
+        self.draw_text(&seed_text, (18.0, top - 60.0), SMALL_TEXT_SCALE, target);
+
+        let row_height = 8.0;
+        if players.len() > 1 {
+            // Break the run down by player.
+            for (i, player) in players.iter().enumerate() {
+                let y = top - 70.0 - row_height * i as f32;
+                self.shape_renderer.draw_filled_rect(18.0, y, 6.0, 6.0, PLAYER_TINTS[i], target);
+                let text = format!("p{} {}", i + 1, player.score());
+                self.draw_text(&text, (28.0, y), SMALL_TEXT_SCALE, target);
+            }
+        } else {
+            match *scores {
+                // A new best made it to the top of the table, so show its
+                // neighbours.
+                Scoreboard::HighScores(high_scores) => if new_best {
+                    for (i, entry) in high_scores.scores().iter().take(PANEL_HIGH_SCORES).enumerate() {
+                        let marker = if high_scores.latest() == Some(i) { ">" } else { " " };
+                        let text = format!("{}{}. {} {}", marker, i + 1, entry.score, entry.date);
+                        let y = top - 70.0 - row_height * i as f32;
+                        self.draw_text(&text, (18.0, y), SMALL_TEXT_SCALE, target);
+                    }
+                },
+                // Put the day's result next to the days before it.
+                Scoreboard::Daily { results, date, .. } => {
+                    for (i, result) in results.days().iter().take(PANEL_HIGH_SCORES).enumerate() {
+                        let marker = if result.date == date { ">" } else { " " };
+                        let text = format!("{}{} {}", marker, result.date, result.score);
+                        let y = top - 70.0 - row_height * i as f32;
+                        self.draw_text(&text, (18.0, y), SMALL_TEXT_SCALE, target);
+                    }
+                },
+            }
+        }
+
+        if world.can_restart() {
+            self.draw_menu(menus.game_over(), bottom + 28.0, target);
+        }
+    }
+
+    // Dims the frozen world and shows either the pause menu or the countdown
+    // to resuming.
+    fn draw_pause<S: Surface>(&mut self, world: &GameWorld, menus: &Menus, target: &mut S) {
+        let mid_point_y = world.mid_point_y() as f32;
+
+        // Darken every other row, since we can't blend shapes.
+        let color = [0.0, 0.0, 0.0];
+        let mut y = 0.0;
+        while y < mid_point_y * 2.0 {
+            self.shape_renderer.draw_filled_rect(0.0, y, GAME_WIDTH, 1.0, color, target);
+            y += 2.0;
+        }
+
+        match world.resume_countdown() {
+            Some(countdown) => {
+                let text = (countdown.ceil() as u32).to_string();
+                self.draw_text_centered(&text, CENTER_X, mid_point_y, SCORE_SCALE, target);
+            },
+            None => {
+                self.draw_text_centered("paused", CENTER_X, mid_point_y + 20.0, SCORE_SCALE, target);
+                self.draw_menu(menus.pause(), mid_point_y, target);
+            },
+        }
+    }
+
+    fn draw_menus<S: Surface>(&mut self, world: &GameWorld, alpha: f32, scores: &Scoreboard,
+                              menus: &Menus, target: &mut S) {
+        let mid_point_y = world.mid_point_y() as f32;
+
+        match menus.screen() {
+            MenuScreen::Title => {
+                let draw_params = SpriteDrawParams::new()
+                    .alpha(true)
+                    .magnify_filter(MagnifySamplerFilter::Nearest);
+                let logo_width = self.assets.zb_logo.size().x as f32;
+                let logo = self.assets.zb_logo.draw(CENTER_X - logo_width / 2.0, mid_point_y + 50.0);
+                self.sprite_renderer.draw(&logo, draw_params, target);
+
+                // Show off the bird, bobbing in the middle of the screen.
+                let bird_y = world.bird().interpolated_position(alpha).y;
+                self.draw_bird_at(world.bird(), cgmath::vec2(CENTER_X - 8.5, bird_y + 10.0), alpha,
+                                  world.run_time(), PLAYER_TINTS[0], target);
+
+                self.draw_menu(menus.title(), mid_point_y - 6.0, target);
+            },
+            MenuScreen::HighScores => match *scores {
+                Scoreboard::HighScores(high_scores) => {
+                    let title = format!("{} high scores", world.mode());
+                    self.draw_high_scores(world, high_scores, &title, target);
+                },
+                Scoreboard::Daily { results, date, .. } => {
+                    self.draw_daily_results(world, results, date, target);
+                },
+            },
+            MenuScreen::Settings => {
+                self.draw_text_centered("settings", CENTER_X, mid_point_y + 40.0, SCORE_SCALE, target);
+                self.draw_menu(menus.settings(), mid_point_y + 10.0, target);
+            },
+            MenuScreen::Controls => {
+                // There's a long list, so it starts higher up.
+                self.draw_text_centered("controls", CENTER_X, mid_point_y + 80.0, MEDIUM_TEXT_SCALE, target);
+                self.draw_menu(menus.controls(), mid_point_y + 74.0, target);
+            },
+        }
+    }
+
+    // Draws a menu's entries downwards from `top`, marking the selected one.
+    fn draw_menu<S: Surface>(&mut self, menu: &Menu, top: f32, target: &mut S) {
+        let entry_height = 12.0;
         let draw_params = SpriteDrawParams::new()
             .alpha(true)
             .magnify_filter(MagnifySamplerFilter::Nearest);
 
-        let score_text: String = world.score().to_string();
-        //let score_text = format!("{:02}", world.score());
-        let text_length = score_text.len();
+        for (i, entry) in menu.entries().iter().enumerate() {
+            let y = top - entry_height * (i + 1) as f32;
+            let pressed = menu.pressed() == Some(i);
+
+            let half_width = if entry.action == MenuAction::Play {
+                let texture = if pressed {
+                    &self.assets.play_button_down
+                } else {
+                    &self.assets.play_button_up
+                };
+                let size = texture.size();
+                let button = texture.draw(CENTER_X - size.x as f32 / 2.0, y - 4.0);
+                self.sprite_renderer.draw(&button, draw_params, target);
+                self.menu_rects.push((CENTER_X - size.x as f32 / 2.0, y - 4.0, size.x as f32, size.y as f32));
+                size.x as f32 / 2.0
+            } else {
+                // Held entries sink a pixel, like the play button.
+                let text_y = if pressed { y - 1.0 } else { y };
+                self.draw_text_centered(&entry.label, CENTER_X, text_y, SMALL_TEXT_SCALE, target);
+                let half_width = self.text_width(&entry.label, SMALL_TEXT_SCALE) / 2.0;
+                // Labels can be clicked a little way around the text too.
+                self.menu_rects.push((CENTER_X - half_width - 4.0, y - 2.0, half_width * 2.0 + 8.0, entry_height));
+                half_width
+            };
+
+            if i == menu.selected() {
+                self.draw_text(">", (CENTER_X - half_width - 8.0, y), SMALL_TEXT_SCALE, target);
+                self.draw_text("<", (CENTER_X + half_width + 4.0, y), SMALL_TEXT_SCALE, target);
+            }
+        }
+    }
+
+    fn draw_high_scores<S: Surface>(&mut self, world: &GameWorld, high_scores: &HighScoreTable,
+                                    title: &str, target: &mut S) {
+        let top = world.mid_point_y() as f32 + 46.0;
+        let row_height = 8.0;
+        let bottom = top - 14.0 - row_height * MAX_HIGH_SCORES as f32;
+
+        let color = [0.0, 0.0, 0.0];
+        self.shape_renderer.draw_filled_rect(8.0, bottom, 120.0, top - bottom, color, target);
+
+        self.draw_text_centered(title, CENTER_X, top - 10.0, SMALL_TEXT_SCALE, target);
+
+        for (i, entry) in high_scores.scores().iter().enumerate() {
+            let marker = if high_scores.latest() == Some(i) { ">" } else { " " };
+            let mut text = format!("{}{:2}. {:4} {}", marker, i + 1, entry.score, entry.date);
+            if let Some(ref name) = entry.name {
+                text.push(' ');
+                text.extend(name.chars().take(6));
+            }
+            let y = top - 14.0 - row_height * (i + 1) as f32;
+            self.draw_text(&text, (12.0, y), SMALL_TEXT_SCALE, target);
+        }
+    }
+
+    // Lists the daily challenges played so far, newest first, marking today's.
+    fn draw_daily_results<S: Surface>(&mut self, world: &GameWorld, results: &DailyResults,
+                                      today: Date, target: &mut S) {
+        let top = world.mid_point_y() as f32 + 46.0;
+        let row_height = 8.0;
+        let bottom = top - 14.0 - row_height * MAX_HIGH_SCORES as f32;
+
+        let color = [0.0, 0.0, 0.0];
+        self.shape_renderer.draw_filled_rect(8.0, bottom, 120.0, top - bottom, color, target);
+
+        self.draw_text_centered("daily results", CENTER_X, top - 10.0, SMALL_TEXT_SCALE, target);
+
+        if !results.has_played(today) {
+            let y = top - 14.0 - row_height;
+            self.draw_text(&format!(">{} -", today), (12.0, y), SMALL_TEXT_SCALE, target);
+        }
+        let first_row = if results.has_played(today) { 0 } else { 1 };
+        for (i, result) in results.days().iter().take(MAX_HIGH_SCORES - first_row).enumerate() {
+            let marker = if result.date == today { ">" } else { " " };
+            let text = format!("{}{} {:4}", marker, result.date, result.score);
+            let y = top - 14.0 - row_height * (first_row + i + 1) as f32;
+            self.draw_text(&text, (12.0, y), SMALL_TEXT_SCALE, target);
+        }
+    }
+
+    fn text_width(&self, text: &str, scale: f32) -> f32 {
+        let positions = self.assets.text_font.parse(text).unwrap();
+        positions.iter()
+            .map(|pos| (pos.screen_rect.x as f32 + pos.screen_rect.width as f32) * scale)
+            .fold(0.0, f32::max)
+    }
+
+    fn draw_text_centered<S: Surface>(&mut self, text: &str, center_x: f32, y: f32, scale: f32,
+                                      target: &mut S) {
+        let x = center_x - self.text_width(text, scale) / 2.0;
+        self.draw_text(text, (x, y), scale, target);
+    }
+
+    // Draws text with its drop shadow, starting at the given position.
+    fn draw_text<S: Surface>(&mut self, text: &str, start_position: (f32, f32), scale: f32,
+                             target: &mut S) {
+        let draw_params = SpriteDrawParams::new()
+            .alpha(true)
+            .magnify_filter(MagnifySamplerFilter::Nearest);
 
         // Draw shadows.
-        let start_position = (68.0 - (3.0 * text_length as f32), world.mid_point_y() as f32 + 64.0);
-        let shadow_positions = self.shadow_font.parse(&score_text).unwrap();
+        let shadow_positions = self.assets.shadow_font.parse(text).unwrap();
         for pos in shadow_positions {
             let offset = (pos.page_rect.x as u32, pos.page_rect.y as u32);
             let size = (pos.page_rect.width, pos.page_rect.height);
             let position = (start_position.0 + pos.screen_rect.x as f32 * scale,
-                            start_position.1 + pos.screen_rect.y as f32 * scale);
+                            start_position.1 - 1.0 + pos.screen_rect.y as f32 * scale);
 
-            let mut sprite = Sprite::with_sub_field(self.shadow_texture.clone(), offset, size);
+            let mut sprite = Sprite::with_sub_field(self.assets.shadow_texture.clone(), offset, size);
             sprite.set_position(cgmath::vec2(position.0.round(), position.1.round()));
             sprite.set_flip_y(true);
             sprite.set_uniform_scale(scale);
@@ -264,16 +746,15 @@ impl<'a> GameRenderer<'a> {
             self.sprite_renderer.draw(&sprite, draw_params, target);
         }
 
-        // Draw numbers.
-        let start_position = (68.0 - (3.0 * text_length as f32), world.mid_point_y() as f32 + 65.0);
-        let text_positions = self.text_font.parse(&score_text).unwrap();
+        // Draw text.
+        let text_positions = self.assets.text_font.parse(text).unwrap();
         for pos in text_positions {
             let offset = (pos.page_rect.x as u32, pos.page_rect.y as u32);
             let size = (pos.page_rect.width, pos.page_rect.height);
             let position = (start_position.0 + pos.screen_rect.x as f32 * scale,
                             start_position.1 + pos.screen_rect.y as f32 * scale);
 
-            let mut sprite = Sprite::with_sub_field(self.text_texture.clone(), offset, size);
+            let mut sprite = Sprite::with_sub_field(self.assets.text_texture.clone(), offset, size);
             sprite.set_position(cgmath::vec2(position.0.round(), position.1.round()));
             sprite.set_flip_y(true);
             sprite.set_uniform_scale(scale);
@@ -0,0 +1,223 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::str::FromStr;
+
+use input::{GameInput, MAX_PLAYERS};
+use mode::GameMode;
+use paths;
+
+
+const MAGIC: &str = "zombie-bird-replay 1";
+
+// Everything needed to play a run back tick for tick, plus how it ended so
+// playback can be checked against the original.
+//
+// On disk this is a short text file:
+//
+//     zombie-bird-replay 1
+//     seed 1234
+//     tick_rate 120
+//     size 136 204
+//     mode classic
+//     score 3
+//     death_tick 1517
+//     flaps 40 95 151 ...
+//
+// with one flaps line per player.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub tick_rate: u32,
+    pub game_size: (f32, f32),
+    // Recordings from before there were modes are all Classic.
+    pub mode: GameMode,
+    // Ticks, counted from the start of the run, on which each player
+    // flapped.
+    pub flaps: Vec<Vec<u32>>,
+    // Best score of any player.
+    pub score: u32,
+    // Tick on which the last bird died, if the run got that far.
+    pub death_tick: Option<u32>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, tick_rate: u32, game_size: (f32, f32)) -> Self {
+        Replay {
+            seed,
+            tick_rate,
+            game_size,
+            mode: GameMode::Classic,
+            flaps: vec![Vec::new()],
+            score: 0,
+            death_tick: None,
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let mut text = String::new();
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut text))
+            .map_err(|e| format!("Could not read replay {}: {}", path.display(), e))?;
+        Replay::parse(&text)
+            .map_err(|e| format!("Invalid replay {}: {}", path.display(), e))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        paths::write_atomically(path.as_ref(), self.to_string().as_bytes())
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines();
+        if lines.next().map(str::trim) != Some(MAGIC) {
+            return Err("not a replay file".to_string());
+        }
+
+        let mut replay = Replay::new(0, 0, (0.0, 0.0));
+        replay.flaps.clear();
+        let mut seen_seed = false;
+        for line in lines {
+            let mut words = line.split_whitespace();
+            let key = match words.next() {
+                Some(key) => key,
+                None => continue,
+            };
+            let values: Vec<&str> = words.collect();
+            match key {
+                "seed" => {
+                    replay.seed = parse_one(key, &values)?;
+                    seen_seed = true;
+                },
+                "tick_rate" => replay.tick_rate = parse_one(key, &values)?,
+                "size" => {
+                    let size: Vec<f32> = parse_all(key, &values)?;
+                    if size.len() != 2 {
+                        return Err("size needs a width and a height".to_string());
+                    }
+                    replay.game_size = (size[0], size[1]);
+                },
+                "mode" => {
+                    let name: String = parse_one(key, &values)?;
+                    replay.mode = GameMode::from_name(&name)
+                        .ok_or(format!("unknown mode \"{}\"", name))?;
+                },
+                "score" => replay.score = parse_one(key, &values)?,
+                "death_tick" => replay.death_tick = Some(parse_one(key, &values)?),
+                "flaps" => replay.flaps.push(parse_all(key, &values)?),
+                _ => return Err(format!("unknown field \"{}\"", key)),
+            }
+        }
+
+        if !seen_seed {
+            return Err("missing seed".to_string());
+        }
+        if replay.tick_rate == 0 {
+            return Err("missing tick_rate".to_string());
+        }
+        if replay.game_size.0 <= 0.0 || replay.game_size.1 <= 0.0 {
+            return Err("missing size".to_string());
+        }
+        if replay.flaps.is_empty() {
+            replay.flaps.push(Vec::new());
+        }
+        if replay.flaps.len() > MAX_PLAYERS {
+            return Err(format!("at most {} players can fly", MAX_PLAYERS));
+        }
+        if replay.flaps.iter().any(|flaps| flaps.windows(2).any(|w| w[0] >= w[1])) {
+            return Err("flap ticks must be increasing".to_string());
+        }
+
+        Ok(replay)
+    }
+
+    // Checks that `played`, a recording made while playing this replay back,
+    // ended the same way the original run did.
+    pub fn check(&self, played: &Replay) -> Result<(), String> {
+        if played.death_tick != self.death_tick {
+            return Err(format!("bird died on tick {:?}, expected {:?}",
+                               played.death_tick, self.death_tick));
+        }
+        if played.score != self.score {
+            return Err(format!("scored {}, expected {}", played.score, self.score));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", MAGIC)?;
+        writeln!(f, "seed {}", self.seed)?;
+        writeln!(f, "tick_rate {}", self.tick_rate)?;
+        writeln!(f, "size {} {}", self.game_size.0, self.game_size.1)?;
+        writeln!(f, "mode {}", self.mode)?;
+        writeln!(f, "score {}", self.score)?;
+        if let Some(tick) = self.death_tick {
+            writeln!(f, "death_tick {}", tick)?;
+        }
+        for flaps in &self.flaps {
+            write!(f, "flaps")?;
+            for tick in flaps {
+                write!(f, " {}", tick)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+// Parses the single value of a `key value` line.
+pub fn parse_one<T: FromStr>(key: &str, values: &[&str]) -> Result<T, String> {
+    if values.len() != 1 {
+        return Err(format!("{} needs exactly one value", key));
+    }
+    values[0].parse().map_err(|_| format!("invalid {} \"{}\"", key, values[0]))
+}
+
+fn parse_all<T: FromStr>(key: &str, values: &[&str]) -> Result<Vec<T>, String> {
+    values.iter()
+        .map(|value| value.parse().map_err(|_| format!("invalid {} \"{}\"", key, value)))
+        .collect()
+}
+
+// Feeds a replay's flaps back into the world, one tick at a time.
+pub struct ReplayPlayer {
+    replay: Replay,
+    // Position of each player's next flap.
+    next_flaps: Vec<usize>,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        let next_flaps = vec![0; replay.flaps.len()];
+        ReplayPlayer {
+            replay,
+            next_flaps,
+        }
+    }
+
+    // Input for the given tick of the run. Ticks must be asked for in order,
+    // though the same tick can be asked for again, e.g. while the run is
+    // paused; asking for tick 0 starts the replay over.
+    pub fn input(&mut self, tick: u32) -> GameInput {
+        let mut input = GameInput::new();
+        for (player, flaps) in self.replay.flaps.iter().enumerate() {
+            let next_flap = &mut self.next_flaps[player];
+            if tick == 0 {
+                *next_flap = 0;
+            }
+            while *next_flap < flaps.len() && flaps[*next_flap] < tick {
+                *next_flap += 1;
+            }
+
+            input.flaps[player] = flaps.get(*next_flap) == Some(&tick);
+        }
+        input
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+}
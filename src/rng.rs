@@ -0,0 +1,33 @@
+use rand::{self, Rng, SeedableRng, XorShiftRng};
+
+use date::Date;
+
+
+// XorShift is cheap and produces the same sequence on every platform, which
+// is all we need for laying out pipes.
+pub type GameRng = XorShiftRng;
+
+pub fn seeded_rng(seed: u64) -> GameRng {
+    // Spread the seed over XorShift's 128 bits of state with splitmix64. This
+    // also keeps us clear of the all-zero state XorShift can't leave.
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    };
+    let (a, b) = (next(), next());
+    XorShiftRng::from_seed([a as u32, (a >> 32) as u32, b as u32, (b >> 32) as u32])
+}
+
+// Seed everyone plays on a given day: the date's digits, e.g. 20161102, so
+// it can also be typed in with --seed to practise an old day.
+pub fn daily_seed(date: Date) -> u64 {
+    date.year as u64 * 10_000 + date.month as u64 * 100 + date.day as u64
+}
+
+pub fn random_seed() -> u64 {
+    rand::thread_rng().gen()
+}
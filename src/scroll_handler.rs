@@ -1,46 +1,122 @@
+use std::f32::consts::PI;
+
 use cgmath::{self, Vector2};
 use nalgebra::{self, Isometry2};
 use ncollide::query;
 use ncollide::shape::Cuboid;
-use rand;
+use rand::Rng;
 use rand::distributions::{IndependentSample, Range};
 
 use bird::Bird;
+use config::{GameConfig, PickupConfig, PipeConfig, ScrollConfig};
+use difficulty::Difficulty;
+use pickup::{self, Pickup, PickupKind};
+use rng::GameRng;
 
 
-// ScrollHandler will use the constants below to determine
-// how fast we need to scroll and also determine
-// the size of the gap between each pair of pipes.
-const SCROLL_SPEED: f32 = -59.0;
-const PIPE_GAP: f32 = 49.0;
+// Where the first pipe of a run starts, off the right edge of the screen.
+const FIRST_PIPE_X: f32 = 210.0;
+const PIPE_WIDTH: u32 = 22;
+// Shortest the lower bar can be.
+const MIN_PIPE_HEIGHT: u32 = 15;
+// Shortest the upper bar can be, however much the heights vary.
+const MIN_UPPER_BAR_HEIGHT: f32 = 8.0;
+// How far the upper bar reaches on above the top of the screen, so a bird
+// pinned against the ceiling can't fly over it.
+const UPPER_BAR_OVERHANG: f32 = 32.0;
 
 
 pub struct ScrollHandler {
     front_grass: Grass,
     back_grass: Grass,
-    pipe1: Pipe,
-    pipe2: Pipe,
-    pipe3: Pipe,
+    // Pipes in the order they come on screen, each one following the one
+    // before it and the first following the last.
+    pipes: Vec<Pipe>,
+    // Determines how fast we need to scroll and the size of the gap between
+    // each pair of pipes.
+    config: ScrollConfig,
+    pipe_config: PipeConfig,
+    pickup_config: PickupConfig,
+    y_pos: f32,
+    game_width: f32,
+    game_height: f32,
 }
 
 impl ScrollHandler {
     // Constructor receives a float that tells us where we need to create our
     // Grass and Pipe objects.
-    pub fn new(y_pos: f32, game_height: f32) -> Self {
-        let front_grass = Grass::new(0.0, y_pos - 11.0, 143, 11, SCROLL_SPEED);
-        let back_grass = Grass::new(front_grass.get_tail_x(), y_pos - 11.0, 143, 11, SCROLL_SPEED);
+    pub fn new(y_pos: f32, game_width: f32, game_height: f32, config: &GameConfig,
+               difficulty: &Difficulty, rng: &mut GameRng) -> Self {
+        let speed = -difficulty.speed;
 
-        let pipe1 = Pipe::new(210.0, y_pos, 22, 60, SCROLL_SPEED, y_pos, game_height);
-        let pipe2 = Pipe::new(pipe1.get_tail_x() + PIPE_GAP, y_pos, 22, 70, SCROLL_SPEED, y_pos, game_height);
-        let pipe3 = Pipe::new(pipe2.get_tail_x() + PIPE_GAP, y_pos, 22, 60, SCROLL_SPEED, y_pos, game_height);
+        let front_grass = Grass::new(0.0, y_pos - 11.0, 143, 11, speed);
+        let back_grass = Grass::new(front_grass.get_tail_x(), y_pos - 11.0, 143, 11, speed);
 
-        ScrollHandler {
+        let mut scroller = ScrollHandler {
             front_grass: front_grass,
             back_grass: back_grass,
 
-            pipe1: pipe1,
-            pipe2: pipe2,
-            pipe3: pipe3,
+            pipes: Vec::new(),
+
+            config: config.scroll,
+            pipe_config: config.pipes,
+            pickup_config: config.pickups,
+            y_pos,
+            game_width,
+            game_height,
+        };
+        scroller.fill_pipes(difficulty, rng);
+        scroller
+    }
+
+    // How many pipes it takes to always have one ready to scroll on screen
+    // while another scrolls off.
+    fn pipe_count(&self) -> usize {
+        let stride = PIPE_WIDTH as f32 + self.config.pipe_spacing;
+        (self.game_width / stride).ceil() as usize + 1
+    }
+
+    // Lays out a fresh row of pipes, as many as the screen needs.
+    fn fill_pipes(&mut self, difficulty: &Difficulty, rng: &mut GameRng) {
+        let speed = -difficulty.speed;
+        let count = self.pipe_count();
+        self.pipes.truncate(count);
+
+        let mut x = FIRST_PIPE_X;
+        for i in 0..count {
+            if i < self.pipes.len() {
+                self.pipes[i].on_restart(x, speed, difficulty, rng);
+            } else {
+                let pipe = Pipe::new(x, self.y_pos, speed, self.game_height, self.pipe_config,
+                                     difficulty, rng);
+                self.pipes.push(pipe);
+            }
+            self.spawn_pickup(i, rng);
+            x = self.pipes[i].get_tail_x() + self.config.pipe_spacing;
+        }
+    }
+
+    // Maybe puts a pickup after the given pipe.
+    fn spawn_pickup(&mut self, i: usize, rng: &mut GameRng) {
+        let config = self.pickup_config;
+        let spacing = self.config.pipe_spacing;
+        let pipe = &mut self.pipes[i];
+
+        let gap_center = pipe.base_height + pipe.base_vertical_gap / 2.0;
+        let width = pipe.width() as f32;
+        pipe.pickup = PickupKind::roll(&config, rng).map(|kind| {
+            let offset = pickup::pickup_offset(width, spacing, gap_center, rng);
+            Pickup::new(kind, offset, config.radius)
+        });
+    }
+
+    // Must be called at the start of every tick, whether or not anything
+    // scrolls during it.
+    pub fn store_previous_state(&mut self) {
+        self.front_grass.scrollable.store_previous_state();
+        self.back_grass.scrollable.store_previous_state();
+        for pipe in &mut self.pipes {
+            pipe.store_previous_state();
         }
     }
 
@@ -48,9 +124,10 @@ impl ScrollHandler {
         self.update_grass(dt);
     }
 
-    pub fn update_running(&mut self, dt: f32) {
+    pub fn update_running(&mut self, dt: f32, bird: &Bird, difficulty: &Difficulty,
+                          rng: &mut GameRng) {
         self.update_grass(dt);
-        self.update_pipes(dt);
+        self.update_pipes(dt, bird, difficulty, rng);
     }
 
     fn update_grass(&mut self, dt: f32) {
@@ -65,27 +142,53 @@ impl ScrollHandler {
         }
     }
 
-    fn update_pipes(&mut self, dt: f32) {
-        self.pipe1.update(dt);
-        self.pipe2.update(dt);
-        self.pipe3.update(dt);
+    fn update_pipes(&mut self, dt: f32, bird: &Bird, difficulty: &Difficulty, rng: &mut GameRng) {
+        for pipe in &mut self.pipes {
+            pipe.update(dt, bird);
+        }
+
+        // Check if any pipe has scrolled offscreen and move it behind the
+        // pipe before it.
+        if let Some(i) = self.pipes.iter().position(Pipe::is_scrolled_left) {
+            let previous = (i + self.pipes.len() - 1) % self.pipes.len();
+            let new_x = self.pipes[previous].get_tail_x() + self.config.pipe_spacing;
+            self.pipes[i].reset(new_x, difficulty, rng);
+            self.spawn_pickup(i, rng);
+
+            // Everything has to move together, so speed changes wait for a
+            // pipe to come around too.
+            self.set_speed(difficulty.speed);
+        }
+    }
+
+    fn set_speed(&mut self, speed: f32) {
+        self.front_grass.scrollable.set_speed(-speed);
+        self.back_grass.scrollable.set_speed(-speed);
+        for pipe in &mut self.pipes {
+            pipe.scrollable.set_speed(-speed);
+        }
+    }
 
-        // Check if any pipe has scrolled offscreen and reset accordingly.
-        if self.pipe1.is_scrolled_left() {
-            self.pipe1.reset(self.pipe3.get_tail_x() + PIPE_GAP);
-        } else if self.pipe2.is_scrolled_left() {
-            self.pipe2.reset(self.pipe1.get_tail_x() + PIPE_GAP);
-        } else if self.pipe3.is_scrolled_left() {
-            self.pipe3.reset(self.pipe2.get_tail_x() + PIPE_GAP);
+    // Applies new tuning values. Everything keeps its place; new spacing
+    // and heights kick in as pipes come back around, and the number of pipes
+    // is only changed on restart.
+    pub fn set_config(&mut self, config: &GameConfig, difficulty: &Difficulty) {
+        self.config = config.scroll;
+        self.pipe_config = config.pipes;
+        self.pickup_config = config.pickups;
+        self.set_speed(difficulty.speed);
+        for pipe in &mut self.pipes {
+            pipe.config = config.pipes;
+            pipe.set_vertical_gap(difficulty.vertical_gap);
         }
     }
 
     pub fn stop(&mut self) {
         self.front_grass.stop();
         self.back_grass.stop();
-        self.pipe1.stop();
-        self.pipe2.stop();
-        self.pipe3.stop();
+        for pipe in &mut self.pipes {
+            pipe.stop();
+        }
     }
 
     pub fn scored(&mut self, bird: &Bird) -> bool {
@@ -102,40 +205,52 @@ impl ScrollHandler {
             }
         };
 
-        check_scored(&mut self.pipe1) || check_scored(&mut self.pipe2) || check_scored(&mut self.pipe3)
+        self.pipes.iter_mut().any(check_scored)
     }
 
-    // Return true if ANY pipe hits the bird.
-    pub fn collides(&self, bird: &Bird) -> bool {
-        self.pipe1.collides(bird) || self.pipe2.collides(bird) || self.pipe3.collides(bird)
+    // Picks up whatever pickup the bird is touching, if any.
+    pub fn collect_pickup(&mut self, bird: &Bird) -> Option<PickupKind> {
+        for pipe in &mut self.pipes {
+            let position = pipe.position();
+            if let Some(pickup) = pipe.pickup.as_mut() {
+                if !pickup.is_collected() && pickup.touches(position, bird) {
+                    pickup.collect();
+                    return Some(pickup.kind());
+                }
+            }
+        }
+        None
     }
 
-    pub fn on_restart(&mut self) {
-        self.front_grass.on_restart(0.0, SCROLL_SPEED);
-        self.back_grass.on_restart(self.front_grass.get_tail_x(), SCROLL_SPEED);
-        self.pipe1.on_restart(210.0, SCROLL_SPEED);
-        self.pipe2.on_restart(self.pipe1.get_tail_x() + PIPE_GAP, SCROLL_SPEED);
-        self.pipe3.on_restart(self.pipe2.get_tail_x() + PIPE_GAP, SCROLL_SPEED);
+    // Return true if ANY pipe hits the bird.
+    pub fn collides(&self, bird: &Bird) -> bool {
+        self.pipes.iter().any(|pipe| pipe.collides(bird))
     }
 
-    pub fn front_grass(&self) -> &Grass {
-        &self.front_grass
+    pub fn on_restart(&mut self, difficulty: &Difficulty, rng: &mut GameRng) {
+        let speed = -difficulty.speed;
+        self.front_grass.on_restart(0.0, speed);
+        self.back_grass.on_restart(self.front_grass.get_tail_x(), speed);
+        self.fill_pipes(difficulty, rng);
     }
 
-    pub fn back_grass(&self) -> &Grass {
-        &self.back_grass
+    // Moves the `i`th pipe to `x` with a still gap of the given size above a
+    // lower bar `height` tall, and no pickup after it. For setting up tests
+    // and tools; panics if there's no such pipe.
+    pub fn place_pipe(&mut self, i: usize, x: f32, height: f32, vertical_gap: f32) {
+        self.pipes[i].place(x, height, vertical_gap);
     }
 
-    pub fn pipe1(&self) -> &Pipe {
-        &self.pipe1
+    pub fn pipes(&self) -> impl Iterator<Item = &Pipe> {
+        self.pipes.iter()
     }
 
-    pub fn pipe2(&self) -> &Pipe {
-        &self.pipe2
+    pub fn front_grass(&self) -> &Grass {
+        &self.front_grass
     }
 
-    pub fn pipe3(&self) -> &Pipe {
-        &self.pipe3
+    pub fn back_grass(&self) -> &Grass {
+        &self.back_grass
     }
 }
 
@@ -179,6 +294,10 @@ impl Grass {
         self.scrollable.position()
     }
 
+    pub fn interpolated_position(&self, alpha: f32) -> Vector2<f32> {
+        self.scrollable.interpolated_position(alpha)
+    }
+
     pub fn width(&self) -> u32 {
         self.scrollable.width()
     }
@@ -188,59 +307,202 @@ impl Grass {
     }
 }
 
-const VERTICAL_GAP: u32 = 45;
 const SKULL_WIDTH: u32 = 24;
 const SKULL_HEIGHT: u32 = 11;
 
+// How a pipe's gap moves, picked each time the pipe comes back around.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PipeBehaviour {
+    Static,
+    // The gap drifts up and down.
+    Bob,
+    // The gap opens wider around its middle and closes back up.
+    Breathe,
+    // The gap slides to a new height once the bird gets close.
+    Slide,
+}
+
 pub struct Pipe {
     scrollable: Scrollable,
-    range: Range<u32>,
     skull_shape: Cuboid<nalgebra::Vector2<f32>>,
     bar_upper_shape: Cuboid<nalgebra::Vector2<f32>>,
     bar_lower_shape: Cuboid<nalgebra::Vector2<f32>>,
+    // Height of the lower bar, and of the gap above it.
+    height: f32,
+    vertical_gap: f32,
+    previous_height: f32,
+    previous_vertical_gap: f32,
+    // Where the gap sits when it isn't moving.
+    base_height: f32,
+    base_vertical_gap: f32,
+    behaviour: PipeBehaviour,
+    config: PipeConfig,
+    // Seconds since the pipe was reset.
+    age: f32,
+    // Where in its cycle a bobbing or breathing pipe starts, in radians.
+    phase: f32,
+    // How far a sliding pipe moves, and how far along it is, from 0 to 1.
+    slide_offset: f32,
+    slide_progress: f32,
+    // Pickup waiting between this pipe and the next.
+    pickup: Option<Pickup>,
     ground_y: f32,
     game_height: f32,
     is_scored: bool,
 }
 
 impl Pipe {
-    fn new(x: f32, y: f32, width: u32, height: u32, scroll_speed: f32, ground_y: f32,
-           game_height: f32) -> Self {
+    fn new(x: f32, y: f32, scroll_speed: f32, game_height: f32, config: PipeConfig,
+           difficulty: &Difficulty, rng: &mut GameRng) -> Self {
+        let width = PIPE_WIDTH;
         let mut pipe = Pipe {
-            scrollable: Scrollable::new(x, y, width, height, scroll_speed),
-            range: Range::new(0, 90),
+            scrollable: Scrollable::new(x, y, width, 0, scroll_speed),
             skull_shape: Cuboid::new(nalgebra::Vector2::new(SKULL_WIDTH as f32 / 2.0, SKULL_HEIGHT as f32 / 2.0)),
-            // NOTE: Correct bar shapes are set in the call to reset().
-            bar_upper_shape: Cuboid::new(nalgebra::Vector2::new(width as f32 / 2.0, height as f32 / 2.0)),
-            bar_lower_shape: Cuboid::new(nalgebra::Vector2::new(width as f32 / 2.0, height as f32 / 2.0)),
-            ground_y: ground_y,
+            // NOTE: Correct heights and bar shapes are set in the call to reset().
+            bar_upper_shape: Cuboid::new(nalgebra::Vector2::new(width as f32 / 2.0, 0.5)),
+            bar_lower_shape: Cuboid::new(nalgebra::Vector2::new(width as f32 / 2.0, 0.5)),
+            height: 0.0,
+            vertical_gap: 0.0,
+            previous_height: 0.0,
+            previous_vertical_gap: 0.0,
+            base_height: 0.0,
+            base_vertical_gap: 0.0,
+            behaviour: PipeBehaviour::Static,
+            config,
+            age: 0.0,
+            phase: 0.0,
+            slide_offset: 0.0,
+            slide_progress: 0.0,
+            pickup: None,
+            ground_y: y,
             game_height: game_height,
             is_scored: false,
         };
-        pipe.reset(x);
+        pipe.reset(x, difficulty, rng);
         pipe
     }
 
-    fn update(&mut self, dt: f32) {
+    fn store_previous_state(&mut self) {
+        self.scrollable.store_previous_state();
+        self.previous_height = self.height;
+        self.previous_vertical_gap = self.vertical_gap;
+    }
+
+    fn update(&mut self, dt: f32, bird: &Bird) {
         self.scrollable.update(dt);
+
+        // Stopped pipes stay put, so the bird doesn't get hit after dying.
+        if self.behaviour == PipeBehaviour::Static || self.scrollable.velocity.x == 0.0 {
+            return;
+        }
+        self.age += dt;
+
+        let config = self.config;
+        match self.behaviour {
+            PipeBehaviour::Static => {},
+            PipeBehaviour::Bob => {
+                let wave = (2.0 * PI * self.age / config.bob_period + self.phase).sin();
+                self.height = self.base_height + config.bob_distance * wave;
+            },
+            PipeBehaviour::Breathe => {
+                let wave = 0.5 - 0.5 * (2.0 * PI * self.age / config.breathe_period + self.phase).cos();
+                let extra = config.breathe_distance * wave;
+                self.vertical_gap = self.base_vertical_gap + extra;
+                self.height = self.base_height - extra / 2.0;
+            },
+            PipeBehaviour::Slide => {
+                let bird_right = bird.position().x + bird.width() as f32;
+                if self.position().x - bird_right < config.slide_trigger_distance {
+                    self.slide_progress = (self.slide_progress + dt / config.slide_time).min(1.0);
+                }
+                // Ease in and out of the slide.
+                let t = self.slide_progress;
+                self.height = self.base_height + self.slide_offset * t * t * (3.0 - 2.0 * t);
+            },
+        }
+
+        self.height = self.height.max(MIN_PIPE_HEIGHT as f32).min(self.max_height());
+        self.update_bar_shapes();
     }
 
-    fn on_restart(&mut self, new_x: f32, scroll_speed: f32) {
+    fn on_restart(&mut self, new_x: f32, scroll_speed: f32, difficulty: &Difficulty,
+                  rng: &mut GameRng) {
         self.scrollable.velocity.x = scroll_speed;
-        self.reset(new_x);
+        self.reset(new_x, difficulty, rng);
     }
 
-    fn reset(&mut self, new_x: f32) {
+    fn reset(&mut self, new_x: f32, difficulty: &Difficulty, rng: &mut GameRng) {
         self.scrollable.reset(new_x);
+        self.base_vertical_gap = difficulty.vertical_gap;
+        self.vertical_gap = self.base_vertical_gap;
+
+        // Keep some of the upper bar on screen however much heights vary.
+        let max_variance = self.max_height() - MIN_PIPE_HEIGHT as f32;
+        let variance = difficulty.height_variance.min(max_variance).max(1.0) as u32;
+        self.base_height = (Range::new(0, variance).ind_sample(rng) + MIN_PIPE_HEIGHT) as f32;
+        self.height = self.base_height;
+
+        let roll: f32 = rng.gen();
+        self.behaviour = if roll < difficulty.bob_chance {
+            PipeBehaviour::Bob
+        } else if roll < difficulty.bob_chance + difficulty.breathe_chance {
+            PipeBehaviour::Breathe
+        } else if roll < difficulty.bob_chance + difficulty.breathe_chance + difficulty.slide_chance {
+            PipeBehaviour::Slide
+        } else {
+            PipeBehaviour::Static
+        };
+        self.age = 0.0;
+        self.phase = 2.0 * PI * rng.gen::<f32>();
+        self.slide_progress = 0.0;
+        // Slide whichever way has room.
+        self.slide_offset = if self.base_height + self.config.slide_distance <= self.max_height() {
+            self.config.slide_distance
+        } else {
+            -self.config.slide_distance
+        };
+
+        // Don't interpolate from wherever the pipe was before.
+        self.previous_height = self.height;
+        self.previous_vertical_gap = self.vertical_gap;
 
-        let mut rng = rand::thread_rng();
-        self.scrollable.height = self.range.ind_sample(&mut rng) + 15;
         self.is_scored = false;
+        self.update_bar_shapes();
+    }
+
+    fn place(&mut self, x: f32, height: f32, vertical_gap: f32) {
+        self.scrollable.reset(x);
+        self.scrollable.store_previous_state();
+        self.base_height = height;
+        self.height = height;
+        self.base_vertical_gap = vertical_gap;
+        self.vertical_gap = vertical_gap;
+        self.previous_height = height;
+        self.previous_vertical_gap = vertical_gap;
+        self.behaviour = PipeBehaviour::Static;
+        self.pickup = None;
+        self.is_scored = false;
+        self.update_bar_shapes();
+    }
+
+    fn set_vertical_gap(&mut self, vertical_gap: f32) {
+        self.base_vertical_gap = vertical_gap;
+        self.vertical_gap = vertical_gap;
+        self.update_bar_shapes();
+    }
+
+    // Tallest the lower bar can get with the current gap.
+    fn max_height(&self) -> f32 {
+        self.game_height - MIN_UPPER_BAR_HEIGHT - self.position().y - self.vertical_gap
+    }
 
-        // Set correct bar shapes for new height.
-        self.bar_lower_shape = Cuboid::new(nalgebra::Vector2::new(self.width() as f32 / 2.0, self.height() as f32 / 2.0));
+    fn update_bar_shapes(&mut self) {
+        // Set correct bar shapes for new height. The upper bar runs from the
+        // top of the gap to past the top of the screen.
+        let gap_top = self.position().y + self.height + self.vertical_gap;
+        self.bar_lower_shape = Cuboid::new(nalgebra::Vector2::new(self.width() as f32 / 2.0, self.height / 2.0));
         self.bar_upper_shape = Cuboid::new(nalgebra::Vector2::new(
-                self.width() as f32 / 2.0, (self.game_height - (self.position().y + self.height() as f32 + VERTICAL_GAP as f32) / 2.0)));
+                self.width() as f32 / 2.0, (self.game_height + UPPER_BAR_OVERHANG - gap_top) / 2.0));
     }
 
     fn stop(&mut self) {
@@ -259,22 +521,66 @@ impl Pipe {
         self.scrollable.position()
     }
 
+    pub fn interpolated_position(&self, alpha: f32) -> Vector2<f32> {
+        self.scrollable.interpolated_position(alpha)
+    }
+
     pub fn width(&self) -> u32 {
         self.scrollable.width()
     }
 
-    pub fn height(&self) -> u32 {
-        self.scrollable.height()
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    pub fn interpolated_height(&self, alpha: f32) -> f32 {
+        self.previous_height + (self.height - self.previous_height) * alpha
+    }
+
+    // Height of the opening between the lower and upper bars.
+    pub fn vertical_gap(&self) -> f32 {
+        self.vertical_gap
+    }
+
+    pub fn interpolated_vertical_gap(&self, alpha: f32) -> f32 {
+        self.previous_vertical_gap + (self.vertical_gap - self.previous_vertical_gap) * alpha
+    }
+
+    pub fn behaviour(&self) -> PipeBehaviour {
+        self.behaviour
+    }
+
+    // The pickup following this pipe, unless it's been collected.
+    pub fn pickup(&self) -> Option<&Pickup> {
+        self.pickup.as_ref().filter(|pickup| !pickup.is_collected())
     }
 
     pub fn upper_bar_height(&self) -> f32 {
         self.bar_upper_shape.half_extents().y * 2.0
     }
 
+    // The upper bar still reaches past the top of the screen between ticks,
+    // so it's as long as the blended gap leaves it.
+    pub fn interpolated_upper_bar_height(&self, alpha: f32) -> f32 {
+        let gap_top = self.interpolated_position(alpha).y + self.interpolated_height(alpha) +
+            self.interpolated_vertical_gap(alpha);
+        self.game_height + UPPER_BAR_OVERHANG - gap_top
+    }
+
     pub fn lower_bar_height(&self) -> f32 {
         self.bar_lower_shape.half_extents().y * 2.0
     }
 
+    // Centers of the lower and upper bars' bounding boxes, in that order.
+    pub fn bar_centers(&self) -> [nalgebra::Vector2<f32>; 2] {
+        let pipe_x_center = self.position().x + self.width() as f32 / 2.0;
+        [
+            nalgebra::Vector2::new(pipe_x_center, self.position().y + self.height / 2.0),
+            nalgebra::Vector2::new(pipe_x_center,
+                                   self.position().y + self.vertical_gap + self.height + self.upper_bar_height() / 2.0),
+        ]
+    }
+
     pub fn collides(&self, bird: &Bird) -> bool {
         let bird_right = bird.position().x + bird.width() as f32;
         if self.position().x < bird_right {
@@ -282,24 +588,16 @@ impl Pipe {
             let (bounding_circle, bird_center) = bird.bounding_circle();
             let ref bird_center = Isometry2::new(bird_center, nalgebra::zero());
 
-            let overlaps = |other_center: &Isometry2<f32>, other_shape| {
+            let overlaps = |other_center: nalgebra::Vector2<f32>, other_shape| {
+                let ref other_center = Isometry2::new(other_center, nalgebra::zero());
                 query::distance(bird_center, bounding_circle,
                                 other_center, other_shape) == 0.0
             };
 
             // Get the Pipe's various bounding boxes.
-            let pipe_x_center = self.position().x + self.width() as f32 / 2.0;
-
-            let bar_lower_center = nalgebra::Vector2::new(pipe_x_center,
-                                                          self.position().y + self.height() as f32 / 2.0);
-            let bar_lower_center = Isometry2::new(bar_lower_center, nalgebra::zero());
-
-            let bar_upper_center = nalgebra::Vector2::new(pipe_x_center,
-                                                          self.position().y + VERTICAL_GAP as f32 + self.height() as f32 + self.upper_bar_height() / 2.0);
-            let bar_upper_center = Isometry2::new(bar_upper_center, nalgebra::zero());
-
-            overlaps(&bar_upper_center, &self.bar_upper_shape) ||
-                overlaps(&bar_lower_center, &self.bar_lower_shape)
+            let [bar_lower_center, bar_upper_center] = self.bar_centers();
+            overlaps(bar_upper_center, &self.bar_upper_shape) ||
+                overlaps(bar_lower_center, &self.bar_lower_shape)
         } else {
             false
         }
@@ -316,6 +614,7 @@ impl Pipe {
 
 struct Scrollable {
     position: Vector2<f32>,
+    previous_position: Vector2<f32>,
     velocity: Vector2<f32>,
     width: u32,
     height: u32,
@@ -326,6 +625,7 @@ impl Scrollable {
     fn new(x: f32, y: f32, width: u32, height: u32, scroll_speed: f32) -> Self {
         Scrollable {
             position: cgmath::vec2(x, y),
+            previous_position: cgmath::vec2(x, y),
             velocity: cgmath::vec2(scroll_speed, 0.0),
             width: width,
             height: height,
@@ -347,12 +647,27 @@ impl Scrollable {
         self.reset(new_x);
     }
 
+    fn store_previous_state(&mut self) {
+        self.previous_position = self.position;
+    }
+
     // Reset: Should Override in subclass for more specific behavior.
     fn reset(&mut self, new_x: f32) {
+        // Carry this tick's movement over to the new position so interpolation
+        // doesn't smear the object across the screen.
+        let moved = self.position.x - self.previous_position.x;
         self.position.x = new_x;
+        self.previous_position.x = new_x - moved;
         self.is_scrolled_left = false;
     }
 
+    // Changes the speed of a moving object. Stopped ones stay stopped.
+    fn set_speed(&mut self, scroll_speed: f32) {
+        if self.velocity.x != 0.0 {
+            self.velocity.x = scroll_speed;
+        }
+    }
+
     fn stop(&mut self) {
         self.velocity.x = 0.0;
     }
@@ -369,6 +684,10 @@ impl Scrollable {
         self.position
     }
 
+    fn interpolated_position(&self, alpha: f32) -> Vector2<f32> {
+        self.previous_position + (self.position - self.previous_position) * alpha
+    }
+
     fn width(&self) -> u32 {
         self.width
     }
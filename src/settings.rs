@@ -0,0 +1,83 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use toml;
+
+use bindings::Bindings;
+use config::DEFAULT_SCALE;
+use paths;
+
+
+pub const MAX_SCALE: u32 = 4;
+
+// Player preferences, saved as TOML in the data directory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    // Window size as a multiple of the configured screen size.
+    pub scale: u32,
+    pub sound: bool,
+    pub bindings: Bindings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            scale: DEFAULT_SCALE,
+            sound: true,
+            bindings: Bindings::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn default_path() -> PathBuf {
+        paths::data_dir().join("settings.toml")
+    }
+
+    // Loads settings from `path`, falling back to the defaults for anything
+    // missing or unreadable.
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+        let mut settings = Settings::default();
+
+        let mut text = String::new();
+        match File::open(path).and_then(|mut file| file.read_to_string(&mut text)) {
+            Ok(_) => {},
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return settings,
+            Err(e) => {
+                println!("Could not read settings from {}: {}", path.display(), e);
+                return settings;
+            },
+        }
+
+        let root: toml::Value = match text.parse() {
+            Ok(root) => root,
+            Err(e) => {
+                println!("Ignoring invalid settings in {}: {}", path.display(), e);
+                return settings;
+            },
+        };
+        if let Some(scale) = root.get("scale").and_then(toml::Value::as_integer) {
+            if (1..=MAX_SCALE as i64).contains(&scale) {
+                settings.scale = scale as u32;
+            }
+        }
+        if let Some(sound) = root.get("sound").and_then(toml::Value::as_bool) {
+            settings.sound = sound;
+        }
+        if let Some(bindings) = root.get("bindings").and_then(toml::Value::as_table) {
+            settings.bindings = Bindings::from_toml(bindings);
+        }
+
+        settings
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut root = toml::value::Table::new();
+        root.insert("scale".to_string(), toml::Value::Integer(self.scale as i64));
+        root.insert("sound".to_string(), toml::Value::Boolean(self.sound));
+        root.insert("bindings".to_string(), toml::Value::Table(self.bindings.to_toml()));
+        paths::write_atomically(path.as_ref(), toml::Value::Table(root).to_string().as_bytes())
+    }
+}
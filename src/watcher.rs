@@ -0,0 +1,58 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+
+// How often files are checked for changes, in seconds.
+const POLL_INTERVAL: f64 = 0.5;
+
+// Notices when any of a set of files changes on disk, by polling their
+// modification times.
+pub struct FileWatcher {
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+    time_to_poll: f64,
+}
+
+impl FileWatcher {
+    pub fn new<I: IntoIterator<Item = PathBuf>>(paths: I) -> Self {
+        let files = paths.into_iter()
+            .map(|path| {
+                let modified = modified_time(&path);
+                (path, modified)
+            })
+            .collect();
+
+        FileWatcher {
+            files,
+            time_to_poll: POLL_INTERVAL,
+        }
+    }
+
+    // Returns the files that changed since the last poll. Files are only
+    // looked at every POLL_INTERVAL seconds, so this is cheap to call every
+    // frame.
+    pub fn poll(&mut self, dt: f64) -> Vec<PathBuf> {
+        self.time_to_poll -= dt;
+        if self.time_to_poll > 0.0 {
+            return Vec::new();
+        }
+        self.time_to_poll = POLL_INTERVAL;
+
+        let mut changed = Vec::new();
+        for (path, last_modified) in &mut self.files {
+            // A file that's gone missing, e.g. halfway through an editor's
+            // save, isn't a change yet; wait for it to come back.
+            let modified = modified_time(path);
+            if modified.is_some() && modified != *last_modified {
+                *last_modified = modified;
+                changed.push(path.clone());
+            }
+        }
+
+        changed
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
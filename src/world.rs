@@ -1,150 +1,735 @@
-use ears::AudioController;
-use midgar::{Midgar, KeyCode};
 use nalgebra::{self, Isometry2};
 use ncollide::query;
 use ncollide::shape::Cuboid;
+use rand::Rng;
 
 use bird::Bird;
+use config::{GameConfig, GAME_OVER_GRACE_TIME, GAME_WIDTH, RESUME_COUNTDOWN, TICK_RATE};
+use difficulty::Difficulty;
+use ghost::{Ghost, GhostFrame};
+use input::{GameInput, MAX_PLAYERS};
+use mode::GameMode;
+use pickup::PickupKind;
+use replay::Replay;
+use rng::{self, GameRng};
 use scroll_handler::ScrollHandler;
-use sounds::Sounds;
 
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum GameState {
     Menu,
     Ready,
     Running,
     GameOver,
     HighScore,
+    Paused,
+}
+
+// Things that happened during an update that the presentation layer may want
+// to react to, e.g. by playing a sound.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameEvent {
+    Flapped,
+    Scored,
+    Died,
+    PickedUp(PickupKind),
+    // The bird hit a pipe but its shield took the blow.
+    ShieldBroken,
+    // The run is over and the game over screen is up.
+    RunEnded,
+}
+
+// Vertical distance between the birds of a multiplayer run as they wait to
+// start.
+const PLAYER_SPACING: f32 = 14.0;
+
+// One player's bird and how their run is going.
+pub struct Player {
+    bird: Bird,
+    score: u32,
+    // Time left on the bird's shield, if it has one.
+    shield_time: f32,
+    // Set while the bird flies through the pipe that broke its shield, so
+    // that pipe doesn't count as a second hit.
+    passing_through: bool,
+    // Set once the bird is down on the ground and out of the run.
+    landed: bool,
+}
+
+impl Player {
+    fn new(bird: Bird) -> Self {
+        Player {
+            bird,
+            score: 0,
+            shield_time: 0.0,
+            passing_through: false,
+            landed: false,
+        }
+    }
+
+    fn on_restart(&mut self, y: f32) {
+        self.bird.on_restart(y);
+        self.score = 0;
+        self.shield_time = 0.0;
+        self.passing_through = false;
+        self.landed = false;
+    }
+
+    pub fn bird(&self) -> &Bird {
+        &self.bird
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn shield_time(&self) -> f32 {
+        self.shield_time
+    }
 }
 
 pub struct GameWorld {
     game_state: GameState,
-    score: u32,
+    // Best score to beat; set by whoever keeps the high score table.
+    high_score: u32,
     run_time: f32,
+    // Time since the run ended.
+    game_over_time: f32,
+    // State to go back to once unpaused.
+    paused_state: GameState,
+    // Time left before a paused run resumes, once resuming has been asked for.
+    resume_countdown: Option<f32>,
+    // Ticks since the current run was (re)started.
+    run_tick: u32,
+    // Tick on which the bird first flapped.
+    start_tick: u32,
+    // Ticks the world is stepped at each second.
+    tick_rate: u32,
 
+    // Time left running in slow motion.
+    slow_motion_time: f32,
+
+    // Seed of the current run's pipe layout.
+    seed: u64,
+    // Whether every run reuses the same seed, or moves on to a new one.
+    reuse_seed: bool,
+    // Whether a run has been played on the current seed yet.
+    seed_used: bool,
+    rng: GameRng,
+    // Recording of the current run.
+    replay: Replay,
+    // The first bird's flight so far this run, for racing against later.
+    track: Vec<GhostFrame>,
+    // Earlier run to race against, shown when its seed and mode match.
+    ghost: Option<Ghost>,
+
+    mode: GameMode,
+    // Config as loaded, before the mode's tweaks.
+    base_config: GameConfig,
+    // Config the run plays with.
+    config: GameConfig,
+    game_size: (f32, f32),
     mid_point_y: u32,
 
-    bird: Bird,
+    players: Vec<Player>,
     scroller: ScrollHandler,
-    ground: Cuboid<nalgebra::Vector2<f32>>,
 
-    sounds: Sounds,
+    events: Vec<GameEvent>,
 }
 
 impl GameWorld {
-    pub fn new(game_width: f32, game_height: f32) -> Self {
+    pub fn new(game_width: f32, game_height: f32, seed: u64, config: GameConfig) -> Self {
         let mid_point_y = (game_height / 2.0) as u32;
+        let mut rng = rng::seeded_rng(seed);
+        let difficulty = Difficulty::at(&config, 0, 0.0);
+        let scroller = ScrollHandler::new(mid_point_y as f32 - 66.0, game_width, game_height,
+                                         &config, &difficulty, &mut rng);
+        let bird = Bird::new(33.0, start_y(mid_point_y, 0, 1), 17, 12, game_height, config.bird);
 
         GameWorld {
             game_state: GameState::Ready,
-            score: 0,
+            high_score: 0,
             run_time: 0.0,
+            game_over_time: 0.0,
+            paused_state: GameState::Ready,
+            resume_countdown: None,
+            run_tick: 0,
+            start_tick: 0,
+            tick_rate: TICK_RATE,
+
+            slow_motion_time: 0.0,
+
+            seed,
+            reuse_seed: false,
+            seed_used: false,
+            rng,
+            replay: Replay::new(seed, TICK_RATE, (game_width, game_height)),
+            track: Vec::new(),
+            ghost: None,
 
+            mode: GameMode::Classic,
+            base_config: config.clone(),
+            config,
+            game_size: (game_width, game_height),
             mid_point_y: mid_point_y,
 
-            bird: Bird::new(33.0, mid_point_y as f32 + 5.0, 17, 12, game_height),
-            scroller: ScrollHandler::new(mid_point_y as f32 - 66.0, game_height),
-            ground: Cuboid::new(nalgebra::Vector2::new(136.0 / 2.0, 11.0 / 2.0)),
+            players: vec![Player::new(bird)],
+            scroller,
 
-            sounds: Sounds::new(),
+            events: Vec::new(),
         }
     }
 
-    pub fn update(&mut self, midgar: &Midgar, dt: f32) {
+    pub fn update(&mut self, input: &GameInput, dt: f32) {
+        self.events.clear();
+        for player in &mut self.players {
+            player.bird.store_previous_state();
+        }
+        self.scroller.store_previous_state();
+
+        // Time stands still while paused, so pauses don't shift the ticks a
+        // replay records.
+        if self.game_state == GameState::Paused {
+            self.update_paused(input, dt);
+            return;
+        }
+
         self.run_time += dt;
 
+        let tick = self.run_tick;
+        self.run_tick += 1;
+        if matches!(self.game_state, GameState::Ready | GameState::Running) {
+            for (i, player) in self.players.iter().enumerate() {
+                if input.flaps[i] && player.bird.is_alive() {
+                    self.replay.flaps[i].push(tick);
+                }
+            }
+        }
+
         match self.game_state {
-            GameState::Menu | GameState::Ready => self.update_ready(midgar, dt),
-            GameState::Running => self.update_running(midgar, dt),
-            GameState::GameOver => self.update_game_over(midgar, dt),
-            _ => {},
+            GameState::Menu => self.update_menu(tick, dt),
+            GameState::Ready => self.update_ready(input, tick, dt),
+            GameState::Running => self.update_running(input, tick, dt),
+            GameState::GameOver | GameState::HighScore => self.update_game_over(input, dt),
+            GameState::Paused => unreachable!(),
+        }
+
+        if self.game_state == GameState::Running {
+            let bird = &self.players[0].bird;
+            self.track.push(GhostFrame {
+                y: bird.position().y,
+                rotation: bird.rotation(),
+            });
+        }
+
+        // Pausing waits for the end of the tick, so flaps made on the same
+        // tick still count.
+        if input.pause {
+            self.pause();
+        }
+    }
+
+    fn update_paused(&mut self, input: &GameInput, dt: f32) {
+        // Pausing again toggles the countdown to resume.
+        if input.pause {
+            self.resume_countdown = match self.resume_countdown {
+                Some(_) => None,
+                None => Some(RESUME_COUNTDOWN),
+            };
+            return;
+        }
+
+        match self.resume_countdown {
+            Some(time_left) if time_left <= dt => {
+                self.resume_countdown = None;
+                self.game_state = self.paused_state;
+            },
+            Some(time_left) => self.resume_countdown = Some(time_left - dt),
+            None => {},
+        }
+    }
+
+    fn update_menu(&mut self, tick: u32, dt: f32) {
+        // Just keep the scenery moving behind the title screen.
+        for player in &mut self.players {
+            player.bird.update_ready(false, tick as f32 * dt);
         }
+        self.scroller.update_ready(dt);
     }
 
-    fn update_ready(&mut self, midgar: &Midgar, dt: f32) {
-        if midgar.input().was_key_pressed(KeyCode::Space) {
+    fn update_ready(&mut self, input: &GameInput, tick: u32, dt: f32) {
+        // The first flap from anyone starts everyone off.
+        if input.flaps.iter().take(self.players.len()).any(|&flap| flap) {
             self.game_state = GameState::Running;
+            self.seed_used = true;
+            self.start_tick = tick;
         }
 
-        self.bird.update_ready(midgar, self.run_time, &mut self.sounds);
+        // Bob using the run's own clock so replays start from the same spot.
+        for (i, player) in self.players.iter_mut().enumerate() {
+            if player.bird.update_ready(input.flaps[i], tick as f32 * dt) {
+                self.events.push(GameEvent::Flapped);
+            }
+        }
         self.scroller.update_ready(dt);
     }
 
-    fn update_running(&mut self, midgar: &Midgar, dt: f32) {
-        self.bird.update_running(midgar, dt, &mut self.sounds);
-        self.scroller.update_running(dt);
+    fn update_running(&mut self, input: &GameInput, tick: u32, dt: f32) {
+        // Power-ups run out in real time, however slow the game is going.
+        let game_dt = dt * self.game_speed();
+        self.slow_motion_time = (self.slow_motion_time - dt).max(0.0);
 
-        if self.scroller.scored(&self.bird) || midgar.input().was_key_pressed(KeyCode::Return) {
-            self.score += 1;
-            // println!("Scored! {}", self.score);
-            self.sounds.coin.play();
+        for (i, player) in self.players.iter_mut().enumerate() {
+            player.shield_time = (player.shield_time - dt).max(0.0);
+            if player.bird.update_running(input.flaps[i], game_dt) {
+                self.events.push(GameEvent::Flapped);
+            }
         }
+        let difficulty = self.difficulty();
+        self.scroller.update_running(game_dt, &self.players[0].bird, &difficulty, &mut self.rng);
 
-        if self.scroller.collides(&self.bird) && self.bird.is_alive() {
-            // Clean up on game over
-            self.scroller.stop();
-            self.bird.die();
-            self.sounds.dead.play();
-        }
-
-        let bird_overlaps_ground = {
-            let (bounding_circle, bird_center) = self.bird.bounding_circle();
-            let ref bird_center = Isometry2::new(bird_center, nalgebra::zero());
-            let ground_center = nalgebra::Vector2::new(136.0 / 2.0, self.mid_point_y as f32 - 71.5);
-            let ref ground_center = Isometry2::new(ground_center, nalgebra::zero());
-            let distance = query::distance(bird_center, bounding_circle,
-                                           ground_center, &self.ground);
-            distance == 0.0
-        };
+        // Every bird flies at the same x, so they all pass a pipe together.
+        if self.scroller.scored(&self.players[0].bird) {
+            for player in self.players.iter_mut().filter(|player| player.bird.is_alive()) {
+                player.score += 1;
+            }
+            // println!("Scored! {}", self.score());
+            self.events.push(GameEvent::Scored);
+        }
 
-        if bird_overlaps_ground {
+        for i in 0..self.players.len() {
+            if self.players[i].bird.is_alive() {
+                if let Some(kind) = self.scroller.collect_pickup(&self.players[i].bird) {
+                    self.apply_pickup(i, kind);
+                }
+            }
+        }
+
+        // Pipes are harmless in modes without dying.
+        let can_die = self.mode.can_die();
+        for i in 0..self.players.len() {
+            let hit_pipe = can_die && self.scroller.collides(&self.players[i].bird);
+            let player = &mut self.players[i];
+            if !hit_pipe {
+                player.passing_through = false;
+            }
+            if hit_pipe && player.bird.is_alive() && !player.passing_through {
+                if player.shield_time > 0.0 {
+                    player.shield_time = 0.0;
+                    player.passing_through = true;
+                    self.events.push(GameEvent::ShieldBroken);
+                } else {
+                    player.bird.die();
+                    self.events.push(GameEvent::Died);
+                }
+            }
+        }
+
+        // Clean up on game over
+        if self.players.iter().all(|player| !player.bird.is_alive()) && self.replay.death_tick.is_none() {
             self.scroller.stop();
-            self.bird.die();
-            self.bird.decelerate();
+            self.replay.death_tick = Some(tick);
+        }
+
+        for i in 0..self.players.len() {
+            if self.players[i].landed || !on_ground(&self.players[i].bird, self.mid_point_y) {
+                continue;
+            }
+
+            let player = &mut self.players[i];
+            if can_die {
+                player.bird.die();
+                player.bird.decelerate();
+                player.landed = true;
+            } else {
+                player.bird.bounce();
+                self.events.push(GameEvent::Flapped);
+            }
+        }
+
+        // The run lasts until the last bird is down.
+        if self.players.iter().all(|player| player.landed) || self.time_left() == Some(0.0) {
+            self.end_run(tick);
+        }
+    }
+
+    // Stops everything and shows the game over screen. The replay records
+    // `tick` as the run's last.
+    fn end_run(&mut self, tick: u32) {
+        self.scroller.stop();
+        for player in &mut self.players {
+            player.bird.die();
+            player.bird.decelerate();
+        }
+        self.game_over_time = 0.0;
+
+        let score = self.score();
+        if score > self.high_score {
+            self.high_score = score;
+            self.game_state = GameState::HighScore;
+        } else {
             self.game_state = GameState::GameOver;
+        }
+
+        if self.replay.death_tick.is_none() {
+            self.replay.death_tick = Some(tick);
+        }
+        self.replay.score = score;
+        self.events.push(GameEvent::RunEnded);
+    }
 
-            // if (score > AssetLoader.getHighScore()) {
-            //     AssetLoader.setHighScore(score);
-            //     currentState = GameState.HIGHSCORE;
-            // }
+    fn apply_pickup(&mut self, player: usize, kind: PickupKind) {
+        let config = self.config.pickups;
+        match kind {
+            PickupKind::Coin => self.players[player].score += config.coin_value,
+            PickupKind::Shield => self.players[player].shield_time = config.shield_time,
+            // Slowing down slows everyone down.
+            PickupKind::SlowMotion => self.slow_motion_time = config.slow_motion_time,
         }
+        self.events.push(GameEvent::PickedUp(kind));
     }
 
-    fn update_game_over(&mut self, midgar: &Midgar, dt: f32) {
-        if midgar.input().was_key_pressed(KeyCode::Space) {
+    fn update_game_over(&mut self, input: &GameInput, dt: f32) {
+        self.game_over_time += dt;
+        if input.restart && self.can_restart() {
             self.restart();
         }
     }
 
     fn restart(&mut self) {
-        self.score = 0;
-        self.bird.on_restart(self.mid_point_y as f32 + 5.0);
-        self.scroller.on_restart();
+        // Chain each run's seed off the previous one, so a session stays
+        // reproducible from its starting seed alone.
+        if self.seed_used && !self.reuse_seed {
+            self.seed = self.rng.gen();
+        }
+        self.seed_used = false;
+        self.resume_countdown = None;
+        self.rng = rng::seeded_rng(self.seed);
+        self.replay = Replay::new(self.seed, self.tick_rate, self.game_size);
+        self.replay.mode = self.mode;
+        self.replay.flaps = vec![Vec::new(); self.players.len()];
+        self.track.clear();
+        self.run_tick = 0;
+        self.start_tick = 0;
+        self.slow_motion_time = 0.0;
+
+        let count = self.players.len();
+        for (i, player) in self.players.iter_mut().enumerate() {
+            player.on_restart(start_y(self.mid_point_y, i, count));
+        }
+        let difficulty = self.difficulty();
+        self.scroller.on_restart(&difficulty, &mut self.rng);
         self.game_state = GameState::Ready;
     }
 
+    // Restarts the run, staying on the title screen if that's where the world
+    // is.
+    fn start_over(&mut self) {
+        let state = self.game_state;
+        self.restart();
+        if state == GameState::Menu {
+            self.game_state = GameState::Menu;
+        }
+    }
+
+    // Starts a fresh run, waiting for the first flap.
+    pub fn start(&mut self) {
+        self.restart();
+    }
+
+    // Abandons the current run and goes back to the title screen.
+    pub fn show_menu(&mut self) {
+        self.restart();
+        self.game_state = GameState::Menu;
+    }
+
+    // Pauses the run if it's in progress, or stops the countdown to resume if
+    // it's already paused.
+    pub fn pause(&mut self) {
+        match self.game_state {
+            GameState::Ready | GameState::Running => {
+                self.paused_state = self.game_state;
+                self.game_state = GameState::Paused;
+            },
+            GameState::Paused => self.resume_countdown = None,
+            _ => {},
+        }
+    }
+
+    // Lays out the runs from the next restart on with `seed`.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.seed_used = false;
+    }
+
+    pub fn set_ghost(&mut self, ghost: Option<Ghost>) {
+        self.ghost = ghost;
+    }
+
+    pub fn ghost(&self) -> Option<&Ghost> {
+        self.ghost.as_ref()
+    }
+
+    // The current run's flight as a ghost to race on later runs.
+    pub fn recorded_ghost(&self) -> Ghost {
+        Ghost {
+            seed: self.seed,
+            mode: self.mode,
+            score: self.players[0].score,
+            frames: self.track.clone(),
+        }
+    }
+
+    // Where the ghost is, blended between the last two ticks, once the bird
+    // has set off. Nothing if there's no ghost for this seed and mode.
+    pub fn ghost_frame(&self, alpha: f32) -> Option<GhostFrame> {
+        let ghost = self.ghost.as_ref().filter(|ghost| ghost.seed == self.seed && ghost.mode == self.mode)?;
+        let (started, alpha) = match self.game_state {
+            GameState::Menu | GameState::Ready => (false, alpha),
+            // Hold still while paused.
+            GameState::Paused => (self.paused_state != GameState::Ready, 1.0),
+            _ => (true, alpha),
+        };
+        if !started {
+            return None;
+        }
+
+        // The tick the bird first flapped on was frame 0.
+        let tick = self.run_tick - 1 - self.start_tick;
+        let previous = ghost.frame(tick.saturating_sub(1))?;
+        let current = ghost.frame(tick)?;
+        Some(previous.lerp(current, alpha))
+    }
+
+    // Sets how many ticks a second the world is stepped at, for playing back
+    // replays recorded at another rate.
+    pub fn set_tick_rate(&mut self, tick_rate: u32) {
+        self.tick_rate = tick_rate;
+        self.replay.tick_rate = tick_rate;
+    }
+
+    pub fn tick_rate(&self) -> u32 {
+        self.tick_rate
+    }
+
+    pub fn set_reuse_seed(&mut self, reuse_seed: bool) {
+        self.reuse_seed = reuse_seed;
+    }
+
+    // Whether every run is played on the same seed.
+    pub fn reuses_seed(&self) -> bool {
+        self.reuse_seed
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // The tick of the current run that the next update will simulate.
+    pub fn run_tick(&self) -> u32 {
+        self.run_tick
+    }
+
+    // Recording of the current run so far, or of the run that just ended.
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    // Events raised by the last update.
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
     pub fn game_state(&self) -> GameState {
         self.game_state
     }
 
+    // Whether the current run has ended, with or without a new high score.
+    pub fn is_game_over(&self) -> bool {
+        matches!(self.game_state, GameState::GameOver | GameState::HighScore)
+    }
+
+    // Whether the game over screen is accepting input yet.
+    pub fn can_restart(&self) -> bool {
+        self.is_game_over() && self.game_over_time >= GAME_OVER_GRACE_TIME
+    }
+
+    pub fn game_over_time(&self) -> f32 {
+        self.game_over_time
+    }
+
+    // State the world was in before it was paused.
+    pub fn paused_state(&self) -> GameState {
+        self.paused_state
+    }
+
+    pub fn resume_countdown(&self) -> Option<f32> {
+        self.resume_countdown
+    }
+
+    // Swaps in new tuning values mid-game. The screen size only applies to
+    // new worlds, since everything is laid out around it.
+    pub fn set_config(&mut self, config: GameConfig) {
+        self.base_config = config;
+        self.apply_config();
+    }
+
+    // Switches to another ruleset, starting the current run over under it.
+    pub fn set_mode(&mut self, mode: GameMode) {
+        self.mode = mode;
+        self.apply_config();
+
+        self.start_over();
+    }
+
+    // Sets how many birds fly, between 1 and MAX_PLAYERS, starting the
+    // current run over with them.
+    pub fn set_players(&mut self, count: usize) {
+        let count = count.max(1).min(MAX_PLAYERS);
+        let game_height = self.game_size.1;
+        let mid_point_y = self.mid_point_y;
+        let bird_config = self.config.bird;
+        self.players = (0..count)
+            .map(|i| {
+                let bird = Bird::new(33.0, start_y(mid_point_y, i, count), 17, 12, game_height, bird_config);
+                Player::new(bird)
+            })
+            .collect();
+
+        self.start_over();
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    fn apply_config(&mut self) {
+        self.config = self.mode.apply(&self.base_config);
+        for player in &mut self.players {
+            player.bird.set_config(self.config.bird);
+        }
+        let difficulty = self.difficulty();
+        self.scroller.set_config(&self.config, &difficulty);
+    }
+
+    pub fn mode(&self) -> GameMode {
+        self.mode
+    }
+
+    // Seconds since the bird first flapped.
+    fn flight_time(&self) -> f32 {
+        match self.game_state {
+            GameState::Ready | GameState::Menu => 0.0,
+            _ => {
+                // The clock stops when the run ends.
+                let end_tick = self.replay.death_tick.map_or(self.run_tick, |tick| tick + 1);
+                (end_tick - self.start_tick) as f32 / self.tick_rate as f32
+            },
+        }
+    }
+
+    // Seconds left before the run ends on its own, in modes with a time
+    // limit.
+    pub fn time_left(&self) -> Option<f32> {
+        let flight_time = self.flight_time();
+        self.mode.time_limit().map(|limit| (limit - flight_time).max(0.0))
+    }
+
+    // How hard the run is right now, going by its score and how long the
+    // bird has been flying.
+    pub fn difficulty(&self) -> Difficulty {
+        Difficulty::at(&self.config, self.score(), self.flight_time())
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    pub fn slow_motion_time(&self) -> f32 {
+        self.slow_motion_time
+    }
+
+    // How fast the game is going compared to real time.
+    pub fn game_speed(&self) -> f32 {
+        if self.slow_motion_time > 0.0 {
+            self.config.pickups.slow_motion_speed
+        } else {
+            1.0
+        }
+    }
+
+    // The best score of any player.
     pub fn score(&self) -> u32 {
-        self.score
+        self.players.iter().map(|player| player.score).max().unwrap_or(0)
+    }
+
+    pub fn high_score(&self) -> u32 {
+        self.high_score
+    }
+
+    pub fn set_high_score(&mut self, high_score: u32) {
+        self.high_score = high_score;
     }
 
     pub fn run_time(&self) -> f32 {
         self.run_time
     }
 
+    pub fn game_size(&self) -> (f32, f32) {
+        self.game_size
+    }
+
     pub fn mid_point_y(&self) -> u32 {
         self.mid_point_y
     }
 
+    // Height of the top of the ground.
+    pub fn ground_y(&self) -> f32 {
+        self.mid_point_y as f32 - 66.0
+    }
+
+    // The first player's bird.
     pub fn bird(&self) -> &Bird {
-        &self.bird
+        &self.players[0].bird
     }
 
     pub fn scroller(&self) -> &ScrollHandler {
         &self.scroller
     }
+
+    // Mutable access for setting up tests and tools.
+    pub fn bird_mut(&mut self) -> &mut Bird {
+        &mut self.players[0].bird
+    }
+
+    pub fn scroller_mut(&mut self) -> &mut ScrollHandler {
+        &mut self.scroller
+    }
+}
+
+// Where a player's bird waits for the run to start, spread out around the
+// middle of the screen.
+fn start_y(mid_point_y: u32, player: usize, count: usize) -> f32 {
+    let offset = player as f32 - (count - 1) as f32 / 2.0;
+    mid_point_y as f32 + 5.0 + offset * PLAYER_SPACING
+}
+
+// The center and half extents of the ground's bounding box, in a world laid
+// out around `mid_point_y`.
+pub fn ground_box(mid_point_y: u32) -> (nalgebra::Vector2<f32>, nalgebra::Vector2<f32>) {
+    let half_width = GAME_WIDTH / 2.0;
+    (nalgebra::Vector2::new(half_width, mid_point_y as f32 - 71.5), nalgebra::Vector2::new(half_width, 11.0 / 2.0))
+}
+
+// Whether the bird touches the ground of a world laid out around
+// `mid_point_y`.
+pub fn on_ground(bird: &Bird, mid_point_y: u32) -> bool {
+    let (ground_center, half_extents) = ground_box(mid_point_y);
+    let ground = Cuboid::new(half_extents);
+    let (bounding_circle, bird_center) = bird.bounding_circle();
+    let ref bird_center = Isometry2::new(bird_center, nalgebra::zero());
+    let ref ground_center = Isometry2::new(ground_center, nalgebra::zero());
+    let distance = query::distance(bird_center, bounding_circle,
+                                   ground_center, &ground);
+    distance == 0.0
 }
@@ -0,0 +1,49 @@
+// Rebinding mustn't leave the menus unusable.
+
+extern crate toml;
+extern crate zombie_bird;
+
+use zombie_bird::bindings::{Action, Binding, Bindings};
+
+
+#[test]
+fn menu_actions_cannot_share_a_control() {
+    let mut bindings = Bindings::default();
+    assert!(bindings.bind(Action::Confirm, Binding::key("Up")).is_err());
+    assert!(bindings.bind(Action::Back, Binding::pad("A")).is_err());
+    assert_eq!(bindings, Bindings::default());
+}
+
+#[test]
+fn other_actions_can_share_with_the_menus() {
+    let mut bindings = Bindings::default();
+    bindings.bind(Action::Confirm, Binding::key("C")).unwrap();
+    bindings.bind(Action::Flap(0), Binding::key("Up")).unwrap();
+    assert_eq!(bindings.get(Action::Confirm)[0], Binding::key("C"));
+    assert_eq!(bindings.get(Action::Flap(0))[0], Binding::key("Up"));
+}
+
+fn load(text: &str) -> Bindings {
+    let table: toml::value::Table = toml::from_str(text).unwrap();
+    Bindings::from_toml(&table)
+}
+
+#[test]
+fn loading_keeps_the_menus_usable() {
+    let defaults = Bindings::default();
+    let bindings = load("confirm = [\"key:Up\"]\nback = []\n");
+    assert_eq!(bindings.get(Action::Confirm), defaults.get(Action::Confirm));
+    assert_eq!(bindings.get(Action::Back), defaults.get(Action::Back));
+    assert_eq!(bindings.get(Action::Up), defaults.get(Action::Up));
+
+    // Each default brings back a clash, so every menu action is reset.
+    let bindings = load("up = [\"key:Return\"]\ndown = [\"key:Return\"]\nconfirm = [\"key:Up\"]\n");
+    assert_eq!(bindings, defaults);
+}
+
+#[test]
+fn loading_keeps_menu_bindings_that_dont_clash() {
+    let bindings = load("confirm = [\"key:C\"]\nflap = [\"key:Up\"]\n");
+    assert_eq!(bindings.get(Action::Confirm), &[Binding::key("C")]);
+    assert_eq!(bindings.get(Action::Flap(0)), &[Binding::key("Up")]);
+}
@@ -0,0 +1,23 @@
+// Daily seeds and results are keyed by date, so only real ones may parse.
+
+extern crate zombie_bird;
+
+use zombie_bird::date::Date;
+
+
+#[test]
+fn days_must_be_in_the_month() {
+    assert_eq!("2026-01-31".parse(), Ok(Date::new(2026, 1, 31)));
+    assert_eq!("2026-04-30".parse(), Ok(Date::new(2026, 4, 30)));
+    assert!("2026-04-31".parse::<Date>().is_err());
+    assert!("2026-02-29".parse::<Date>().is_err());
+    assert!("2026-02-31".parse::<Date>().is_err());
+    assert!("2026-02-00".parse::<Date>().is_err());
+}
+
+#[test]
+fn leap_years_have_a_29th_of_february() {
+    assert_eq!("2024-02-29".parse(), Ok(Date::new(2024, 2, 29)));
+    assert_eq!("2000-02-29".parse(), Ok(Date::new(2000, 2, 29)));
+    assert!("2100-02-29".parse::<Date>().is_err());
+}
@@ -0,0 +1,72 @@
+// Replays have to play back the same however the viewer pauses them.
+
+extern crate zombie_bird;
+
+use zombie_bird::config::{GameConfig, TICK_RATE};
+use zombie_bird::headless;
+use zombie_bird::input::GameInput;
+use zombie_bird::replay::{Replay, ReplayPlayer};
+use zombie_bird::world::GameState;
+
+
+const SEED: u64 = 7;
+// Ticks to wait while paused before asking to resume.
+const PAUSED_TICKS: u32 = 30;
+// Ticks the recorded run flaps on.
+const FLAP_TICKS: [u32; 6] = [0, 40, 80, 120, 160, 200];
+
+// A short run: a few flaps, then a fall to the ground.
+fn record() -> Replay {
+    let config = GameConfig::default();
+    let mut world = headless::new_world(SEED, &config);
+    let dt = 1.0 / TICK_RATE as f32;
+    while !world.is_game_over() {
+        let mut input = GameInput::new();
+        input.flaps[0] = FLAP_TICKS.contains(&world.run_tick());
+        world.update(&input, dt);
+    }
+    world.replay().clone()
+}
+
+// Plays the replay back the way the game does, pausing on `pause_tick` and
+// resuming a while later.
+fn play_with_pause(replay: &Replay, pause_tick: u32) -> Replay {
+    let config = GameConfig::default();
+    let mut world = headless::new_world(SEED, &config);
+    let dt = 1.0 / replay.tick_rate as f32;
+    let mut player = ReplayPlayer::new(replay.clone());
+    let mut paused = false;
+    let mut paused_ticks = 0;
+    while !world.is_game_over() {
+        let mut input = player.input(world.run_tick());
+        if world.game_state() == GameState::Paused {
+            paused_ticks += 1;
+            input.pause = paused_ticks == PAUSED_TICKS;
+        } else if world.run_tick() == pause_tick && !paused {
+            input.pause = true;
+            paused = true;
+        }
+        world.update(&input, dt);
+    }
+    assert!(paused && paused_ticks > PAUSED_TICKS, "the replay was never paused");
+    world.replay().clone()
+}
+
+#[test]
+fn pausing_on_a_flap_keeps_the_flap() {
+    let replay = record();
+    assert!(replay.flaps[0].len() > 2, "the recording should flap a few times");
+
+    let flap_tick = replay.flaps[0][1];
+    let played = play_with_pause(&replay, flap_tick);
+    assert_eq!(played.flaps, replay.flaps);
+    replay.check(&played).unwrap();
+}
+
+#[test]
+fn pausing_between_flaps_changes_nothing() {
+    let replay = record();
+    let played = play_with_pause(&replay, replay.flaps[0][1] + 5);
+    assert_eq!(played.flaps, replay.flaps);
+    replay.check(&played).unwrap();
+}
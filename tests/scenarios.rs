@@ -0,0 +1,261 @@
+// Regression tests for collisions, scoring and ground deaths, described as
+// scenarios in tests/scenarios/*.txt. Every file there is run, so adding a
+// case doesn't take any code. A scenario sets up a running Classic world,
+// steps it and checks what happened:
+//
+//     # Comments run to the end of the line.
+//     scenario clipping the top of a skull
+//     bird 33 76 0      # x, y of the bird's bottom left, vertical velocity
+//     pipe 30 40 45     # x, lower bar height, gap height
+//     expect collision
+//     step 1            # ticks to run
+//     expect dead
+//
+// The game is 136 by 204 with the top of the ground at y = 36, and y points
+// up. `pipe` lines place the world's pipes in order; the rest wait far off to
+// the right. Placed pipes stand still apart from scrolling, and pickups are
+// turned off.
+//
+// Commands:
+//     mode <name>       play the given mode; must come before anything else
+//     bird <x> <y> <vy> moves the bird
+//     pipe <x> <h> <g>  places the next pipe
+//     flap              flaps on the next tick
+//     step <ticks>      runs the world
+//     expect <check>    one of collision, no-collision, ground, no-ground,
+//                       alive, dead, running, game-over, scored, not-scored
+//                       or score <n>
+//
+// `scored` and `not-scored` ask ScrollHandler::scored directly, which marks
+// the pipe as passed just like the game does, so a tick after `expect scored`
+// won't score it again.
+
+extern crate zombie_bird;
+
+use std::fs;
+use std::path::Path;
+
+use zombie_bird::bird::Bird;
+use zombie_bird::config::{GameConfig, TICK_RATE};
+use zombie_bird::headless;
+use zombie_bird::input::GameInput;
+use zombie_bird::mode::GameMode;
+use zombie_bird::world::{self, GameState, GameWorld};
+
+
+// Where pipes a scenario doesn't place wait, well out of the way.
+const UNUSED_PIPE_X: f32 = 1000.0;
+
+#[derive(Debug)]
+enum Command {
+    Bird(f32, f32, f32),
+    Pipe(f32, f32, f32),
+    Flap,
+    Step(u32),
+    // The check, and how the scenario wrote it.
+    Expect(Check, String),
+}
+
+#[derive(Debug)]
+enum Check {
+    Collision(bool),
+    Ground(bool),
+    Alive(bool),
+    Running,
+    GameOver,
+    Scored(bool),
+    Score(u32),
+}
+
+struct Scenario {
+    name: String,
+    mode: GameMode,
+    // Commands with the lines they're on.
+    commands: Vec<(usize, Command)>,
+}
+
+fn parse_numbers(args: &[&str], count: usize) -> Result<Vec<f32>, String> {
+    if args.len() != count {
+        return Err(format!("expected {} numbers, got {}", count, args.len()));
+    }
+    args.iter()
+        .map(|arg| arg.parse().map_err(|_| format!("\"{}\" isn't a number", arg)))
+        .collect()
+}
+
+fn parse_check(args: &[&str]) -> Result<Check, String> {
+    let check = match *args {
+        ["collision"] => Check::Collision(true),
+        ["no-collision"] => Check::Collision(false),
+        ["ground"] => Check::Ground(true),
+        ["no-ground"] => Check::Ground(false),
+        ["alive"] => Check::Alive(true),
+        ["dead"] => Check::Alive(false),
+        ["running"] => Check::Running,
+        ["game-over"] => Check::GameOver,
+        ["scored"] => Check::Scored(true),
+        ["not-scored"] => Check::Scored(false),
+        ["score", score] => Check::Score(score.parse().map_err(|_| format!("\"{}\" isn't a score", score))?),
+        _ => return Err(format!("unknown check \"{}\"", args.join(" "))),
+    };
+    Ok(check)
+}
+
+fn parse(text: &str) -> Result<Vec<Scenario>, String> {
+    let mut scenarios: Vec<Scenario> = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        let words: Vec<&str> = line.split('#').next().unwrap().split_whitespace().collect();
+        let (command, args) = match words.split_first() {
+            Some((command, args)) => (*command, args),
+            None => continue,
+        };
+        let error = |message: String| format!("line {}: {}", line_number, message);
+
+        if command == "scenario" {
+            if args.is_empty() {
+                return Err(error("scenario needs a name".to_string()));
+            }
+            scenarios.push(Scenario {
+                name: args.join(" "),
+                mode: GameMode::Classic,
+                commands: Vec::new(),
+            });
+            continue;
+        }
+        let scenario = scenarios.last_mut()
+            .ok_or_else(|| error(format!("{} before the first scenario", command)))?;
+
+        let parsed = match command {
+            "mode" => {
+                if !scenario.commands.is_empty() {
+                    return Err(error("mode must come first".to_string()));
+                }
+                let name = args.first().cloned().unwrap_or("");
+                scenario.mode = GameMode::from_name(name)
+                    .ok_or_else(|| error(format!("unknown mode \"{}\"", name)))?;
+                continue;
+            },
+            "bird" => parse_numbers(args, 3).map(|n| Command::Bird(n[0], n[1], n[2])),
+            "pipe" => parse_numbers(args, 3).map(|n| Command::Pipe(n[0], n[1], n[2])),
+            "flap" => Ok(Command::Flap),
+            "step" => args.first()
+                .and_then(|ticks| ticks.parse().ok())
+                .map(Command::Step)
+                .ok_or_else(|| "step needs a number of ticks".to_string()),
+            "expect" => parse_check(args).map(|check| Command::Expect(check, args.join(" "))),
+            _ => Err(format!("unknown command \"{}\"", command)),
+        };
+        scenario.commands.push((line_number, parsed.map_err(error)?));
+    }
+    Ok(scenarios)
+}
+
+// A world of the given mode, just after the first flap, with every pipe out
+// of the way.
+fn start(mode: GameMode) -> GameWorld {
+    let mut config = GameConfig::default();
+    config.pickups.coin_chance = 0.0;
+    config.pickups.shield_chance = 0.0;
+    config.pickups.slow_motion_chance = 0.0;
+
+    let mut world = headless::new_world(1, &config);
+    world.set_mode(mode);
+    let mut input = GameInput::new();
+    input.flaps[0] = true;
+    world.update(&input, 1.0 / TICK_RATE as f32);
+    assert_eq!(world.game_state(), GameState::Running);
+
+    let pipes = world.scroller().pipes().count();
+    for i in 0..pipes {
+        world.scroller_mut().place_pipe(i, UNUSED_PIPE_X + 100.0 * i as f32, 40.0, 45.0);
+    }
+    world
+}
+
+// Runs the scenario, returning what it found wrong.
+fn run(scenario: &Scenario) -> Vec<String> {
+    let mut world = start(scenario.mode);
+    let dt = 1.0 / TICK_RATE as f32;
+    let mut input = GameInput::new();
+    let mut placed_pipes = 0;
+    let mut failures = Vec::new();
+
+    for &(line, ref command) in &scenario.commands {
+        let mut fail = |message: String| {
+            failures.push(format!("line {}: {}: {}", line, scenario.name, message));
+        };
+        match *command {
+            Command::Bird(x, y, velocity_y) => world.bird_mut().place(x, y, velocity_y),
+            Command::Pipe(x, height, vertical_gap) => {
+                if placed_pipes == world.scroller().pipes().count() {
+                    fail(format!("the world only has {} pipes", placed_pipes));
+                    break;
+                }
+                world.scroller_mut().place_pipe(placed_pipes, x, height, vertical_gap);
+                placed_pipes += 1;
+            },
+            Command::Flap => input.flaps[0] = true,
+            Command::Step(ticks) => {
+                for _ in 0..ticks {
+                    world.update(&input, dt);
+                    input = GameInput::new();
+                }
+            },
+            Command::Expect(ref check, ref text) => {
+                let passed = match *check {
+                    Check::Collision(expected) => world.scroller().collides(world.bird()) == expected,
+                    Check::Ground(expected) => world::on_ground(world.bird(), world.mid_point_y()) == expected,
+                    Check::Alive(expected) => world.bird().is_alive() == expected,
+                    Check::Running => world.game_state() == GameState::Running,
+                    Check::GameOver => world.is_game_over(),
+                    Check::Scored(expected) => {
+                        // The world's own bird can't be lent out while the
+                        // scroller is borrowed, so score a copy of it.
+                        let position = world.bird().position();
+                        let (_, game_height) = world.game_size();
+                        let bird = Bird::new(position.x, position.y, world.bird().width(), world.bird().height(),
+                                             game_height, world.config().bird);
+                        world.scroller_mut().scored(&bird) == expected
+                    },
+                    Check::Score(expected) => world.score() == expected,
+                };
+                if !passed {
+                    let position = world.bird().position();
+                    fail(format!("expect {} failed with the bird at ({}, {}), score {}",
+                                 text, position.x, position.y, world.score()));
+                }
+            },
+        }
+    }
+    failures
+}
+
+#[test]
+fn scenarios() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/scenarios");
+    let mut paths: Vec<_> = fs::read_dir(&dir)
+        .expect("can't read the scenarios directory")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().map_or(false, |extension| extension == "txt"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "no scenarios in {}", dir.display());
+
+    let mut failures = Vec::new();
+    for path in &paths {
+        let text = fs::read_to_string(path).unwrap();
+        let file = path.file_name().unwrap().to_string_lossy();
+        let scenarios = match parse(&text) {
+            Ok(scenarios) => scenarios,
+            Err(e) => {
+                failures.push(format!("{}: {}", file, e));
+                continue;
+            },
+        };
+        for scenario in &scenarios {
+            failures.extend(run(scenario).into_iter().map(|failure| format!("{}: {}", file, failure)));
+        }
+    }
+    assert!(failures.is_empty(), "{} failed:\n{}", failures.len(), failures.join("\n"));
+}